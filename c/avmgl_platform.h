@@ -0,0 +1,80 @@
+// Platform hooks called by the avmgl-rs NIFs
+//
+// The AtomVM platform port implements these functions to give the NIFs its
+// DSI host, LTDC, backlight and framebuffer memory. Hooks returning int
+// return 0 on success and a non-zero status on failure.
+//
+// Keep in sync with src/platform.rs and LayerConfig in src/traits.rs.
+
+#ifndef AVMGL_PLATFORM_H
+#define AVMGL_PLATFORM_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+// LTDC PF register values, the pixel_format of a LayerConfig
+#define AVMGL_PF_ARGB8888 0
+#define AVMGL_PF_RGB888 1
+#define AVMGL_PF_RGB565 2
+#define AVMGL_PF_ARGB1555 3
+#define AVMGL_PF_ARGB4444 4
+#define AVMGL_PF_L8 5
+#define AVMGL_PF_AL44 6
+#define AVMGL_PF_AL88 7
+
+// Blending factors, the blending of a LayerConfig
+#define AVMGL_BLENDING_CONSTANT_ALPHA 0
+#define AVMGL_BLENDING_PIXEL_ALPHA_TIMES_CONSTANT_ALPHA 1
+
+typedef struct {
+    uint8_t  layer;               // 0 or 1
+    // Window in screen coordinates, x0..x1 and y0..y1 with exclusive ends
+    uint16_t window_x0;
+    uint16_t window_x1;
+    uint16_t window_y0;
+    uint16_t window_y1;
+    uint32_t pixel_format;        // AVMGL_PF_*
+    uint8_t  alpha;               // constant alpha
+    bool     red_blue_swap;
+    uint32_t framebuffer_address;
+    uint16_t framebuffer_pitch;   // line length in bytes
+    uint32_t blending;            // AVMGL_BLENDING_*
+    uint32_t default_color;       // ARGB8888 outside the window
+    bool     color_key_enabled;
+    uint32_t color_key;           // RGB888 color made transparent
+    bool     enabled;
+} LayerConfig;
+
+_Static_assert(sizeof(LayerConfig) == 48, "LayerConfig must match the Rust layout");
+
+// params[0] is the DCS command, followed by nb_params parameter bytes
+int  avmgl_dsi_send_dcs_command(size_t nb_params, const uint8_t *params);
+void avmgl_dsi_delay_ms(uint32_t ms);
+bool avmgl_dsi_is_ready(void);
+int  avmgl_dsi_reset(void);
+int  avmgl_dsi_set_max_return_packet_size(uint16_t size);
+int  avmgl_dsi_dcs_read(uint8_t command, uint8_t *buffer, size_t len);
+// Blocks until the panel's tearing effect line pulses
+int  avmgl_dsi_wait_for_te(void);
+
+int  avmgl_ltdc_configure_layer(uint8_t layer, const LayerConfig *config);
+int  avmgl_ltdc_enable(void);
+int  avmgl_ltdc_disable(void);
+int  avmgl_ltdc_enable_layer(uint8_t layer);
+int  avmgl_ltdc_disable_layer(uint8_t layer);
+// Load len 0x00RRGGBB entries into the layer's CLUT and enable it
+int  avmgl_ltdc_load_clut(uint8_t layer, const uint32_t *colors, size_t len);
+int  avmgl_ltdc_set_framebuffer(uint8_t layer, uint32_t address);
+void avmgl_ltdc_get_dimensions(uint16_t *width, uint16_t *height);
+// Block until vertical blanking starts
+int  avmgl_ltdc_wait_for_vblank(void);
+
+// PWM backlight, duty cycle from 0 (dark) to 255 (full)
+int  avmgl_backlight_set_enabled(bool enabled);
+int  avmgl_backlight_set_duty(uint8_t duty);
+
+// RGB565 framebuffer memory; writes its length in pixels to len
+uint16_t *avmgl_framebuffer(size_t *len);
+
+#endif
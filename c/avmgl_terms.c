@@ -0,0 +1,26 @@
+// Term construction helpers for the avmgl-rs NIFs
+//
+// avmnif-rs cannot allocate boxed terms yet, so tuples are built here on
+// the process heap using the libAtomVM API.
+
+#include <stddef.h>
+
+#include "context.h"
+#include "memory.h"
+#include "term.h"
+
+// Returns 0 when the heap cannot be grown. Elements are GC roots and are
+// updated in place if the heap moves.
+term avmgl_make_tuple(Context *ctx, term *elements, size_t count)
+{
+    if (UNLIKELY(memory_ensure_free_with_roots(ctx, TUPLE_SIZE(count), count, elements, MEMORY_CAN_SHRINK) != MEMORY_GC_OK)) {
+        return 0;
+    }
+
+    term tuple = term_alloc_tuple(count, &ctx->heap);
+    for (size_t i = 0; i < count; i++) {
+        term_put_tuple_element(tuple, i, elements[i]);
+    }
+
+    return tuple;
+}
@@ -0,0 +1,85 @@
+//! Board backlights
+//!
+//! Panels dim themselves through the DCS brightness register unless the
+//! driver is lent a `Backlight`. The DSI drivers hold it as a
+//! `dyn DynBacklight`, so they are not generic over the board's backlight and
+//! the DCS path is the same code with or without one. `GpioBacklight`
+//! switches a plain pin for boards that can only turn the light on and off.
+//! PWM backlights implement `Backlight` on top of their timer, as
+//! `platform::PlatformBacklight` does.
+
+use crate::traits::{Backlight, OutputPin};
+
+/// Object-safe view of a `Backlight`
+///
+/// The drivers report any backlight failure as `CommError`, so the error is
+/// dropped here instead of naming the backlight's error type.
+pub(crate) trait DynBacklight {
+    fn set_enabled(&mut self, enabled: bool) -> Result<(), ()>;
+
+    fn set_level(&mut self, level: u8) -> Result<(), ()>;
+
+    fn perceptual_level(&self, brightness: u8) -> u8;
+}
+
+impl<B: Backlight> DynBacklight for B {
+    fn set_enabled(&mut self, enabled: bool) -> Result<(), ()> {
+        Backlight::set_enabled(self, enabled).map_err(drop)
+    }
+
+    fn set_level(&mut self, level: u8) -> Result<(), ()> {
+        Backlight::set_level(self, level).map_err(drop)
+    }
+
+    fn perceptual_level(&self, brightness: u8) -> u8 {
+        Backlight::perceptual_level(self, brightness)
+    }
+}
+
+/// On/off backlight on a GPIO pin
+///
+/// Any non-zero level lights the backlight fully.
+#[derive(Debug)]
+pub struct GpioBacklight<P: OutputPin> {
+    pin: P,
+    enabled: bool,
+    level: u8,
+}
+
+impl<P: OutputPin> GpioBacklight<P> {
+    /// Wrap `pin`, assuming the backlight is currently lit
+    pub fn new(pin: P) -> Self {
+        Self { pin, enabled: true, level: u8::MAX }
+    }
+
+    /// Release the pin
+    pub fn into_inner(self) -> P {
+        self.pin
+    }
+
+    fn update(&mut self) -> Result<(), P::Error> {
+        if self.enabled && self.level > 0 {
+            self.pin.set_high()
+        } else {
+            self.pin.set_low()
+        }
+    }
+}
+
+impl<P: OutputPin> Backlight for GpioBacklight<P> {
+    type Error = P::Error;
+
+    fn set_enabled(&mut self, enabled: bool) -> Result<(), P::Error> {
+        self.enabled = enabled;
+        self.update()
+    }
+
+    fn set_level(&mut self, level: u8) -> Result<(), P::Error> {
+        self.level = level;
+        self.update()
+    }
+
+    fn perceptual_level(&self, brightness: u8) -> u8 {
+        brightness
+    }
+}
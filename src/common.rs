@@ -20,4 +20,46 @@ pub fn rgb565_to_rgb888(color: u16) -> (u8, u8, u8) {
     let b8 = (b << 3) | (b >> 2);
     
     (r8, g8, b8)
-}
\ No newline at end of file
+}
+/// Interval between the brightness steps of a fade
+pub const FADE_STEP_MS: u32 = 10;
+
+/// Brightness levels of a linear fade, one every `step_ms`
+///
+/// Ends exactly at the target level. Short or shallow fades take fewer
+/// steps, never more than one per brightness level.
+#[derive(Debug, Clone)]
+pub struct Fade {
+    from: u8,
+    to: u8,
+    step: u32,
+    steps: u32,
+    step_ms: u32,
+}
+
+impl Fade {
+    /// Fade from `from` to `to` over about `duration_ms`
+    pub fn new(from: u8, to: u8, duration_ms: u32) -> Self {
+        let distance = from.abs_diff(to) as u32;
+        let steps = (duration_ms / FADE_STEP_MS).clamp(1, distance.max(1));
+        Self { from, to, step: 0, steps, step_ms: duration_ms / steps }
+    }
+
+    /// Get the delay between two levels
+    pub fn step_ms(&self) -> u32 {
+        self.step_ms
+    }
+}
+
+impl Iterator for Fade {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if self.step == self.steps {
+            return None;
+        }
+        self.step += 1;
+        let delta = (self.to as i32 - self.from as i32) * self.step as i32 / self.steps as i32;
+        Some((self.from as i32 + delta) as u8)
+    }
+}
@@ -0,0 +1,89 @@
+//! MIPI DBI drawing helpers
+//!
+//! Panels behind a `DisplayBus` keep their own frame memory, so drawing means
+//! opening a window with CASET/PASET and streaming pixels after RAMWR. The
+//! generic `DbiPanelDriver` is built on these helpers.
+
+use crate::dcs::{ColorFormat, SET_COLUMN_ADDRESS, SET_PAGE_ADDRESS, WRITE_MEMORY_START};
+use crate::error::BusError;
+use crate::traits::{color, pixel::Rgb565, DisplayBus};
+
+/// Timing parameters shared by DBI panels
+pub mod timing {
+    /// Delays in milliseconds
+    pub const RESET_DELAY_MS: u32 = 120;
+    pub const SLEEP_OUT_DELAY_MS: u32 = 120;
+    pub const DISPLAY_ON_DELAY_MS: u32 = 20;
+    pub const DISPLAY_OFF_DELAY_MS: u32 = 20;
+    /// SLPIN must be followed by 120 ms before SLPOUT
+    pub const SLEEP_IN_DELAY_MS: u32 = 120;
+    pub const CMD_DELAY_MS: u32 = 1;
+}
+
+/// A pixel encoded for the bus, most significant byte first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EncodedPixel {
+    bytes: [u8; 3],
+    len: usize,
+}
+
+impl EncodedPixel {
+    /// Encode `color` for the interface format selected with COLMOD
+    ///
+    /// In 18-bit mode each channel is sent in the upper six bits of a byte.
+    pub fn new(color: Rgb565, color_format: ColorFormat) -> Self {
+        if color_format == ColorFormat::Rgb666 {
+            let (r, g, b) = color::rgb565_to_rgb888(color.0);
+            Self { bytes: [r, g, b], len: 3 }
+        } else {
+            let [hi, lo] = color.0.to_be_bytes();
+            Self { bytes: [hi, lo, 0], len: 2 }
+        }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+/// Send `command` followed by `data`, failing with the command as opcode
+fn send<B: DisplayBus>(bus: &mut B, command: u8, data: &[u8]) -> Result<(), BusError<B::Error>> {
+    bus.write_command(command)
+        .and_then(|_| bus.write_data(data))
+        .map_err(|error| BusError::command(command, error))
+}
+
+/// Restrict memory writes to the inclusive window `(x0, y0)..=(x1, y1)`
+pub fn set_window<B: DisplayBus>(bus: &mut B, x0: u16, y0: u16, x1: u16, y1: u16) -> Result<(), BusError<B::Error>> {
+    let [x0h, x0l] = x0.to_be_bytes();
+    let [x1h, x1l] = x1.to_be_bytes();
+    let [y0h, y0l] = y0.to_be_bytes();
+    let [y1h, y1l] = y1.to_be_bytes();
+
+    send(bus, SET_COLUMN_ADDRESS, &[x0h, x0l, x1h, x1l])?;
+    send(bus, SET_PAGE_ADDRESS, &[y0h, y0l, y1h, y1l])
+}
+
+/// Write `pixel` into the window opened by `set_window`
+pub fn write_pixel<B: DisplayBus>(bus: &mut B, pixel: EncodedPixel) -> Result<(), BusError<B::Error>> {
+    send(bus, WRITE_MEMORY_START, pixel.as_bytes())
+}
+
+/// Fill a `width` x `height` rectangle at `(x, y)` with `color`
+///
+/// The rectangle must be non-empty and already clipped to the panel.
+pub fn fill_window<B: DisplayBus>(
+    bus: &mut B,
+    x: u16,
+    y: u16,
+    width: u16,
+    height: u16,
+    color: Rgb565,
+    color_format: ColorFormat,
+) -> Result<(), BusError<B::Error>> {
+    set_window(bus, x, y, x + width - 1, y + height - 1)?;
+    let pixel = EncodedPixel::new(color, color_format);
+    bus.write_command(WRITE_MEMORY_START)
+        .and_then(|_| bus.write_repeated(pixel.as_bytes(), width as u32 * height as u32))
+        .map_err(|error| BusError::command(WRITE_MEMORY_START, error))
+}
@@ -0,0 +1,395 @@
+//! Generic DBI panel driver
+//!
+//! The ILI9341 and ST7789 differ only in their vendor registers and in how
+//! MADCTL maps an orientation onto the frame memory. `DbiPanelDriver`
+//! implements drawing, orientation, power modes and brightness once over
+//! any `DisplayBus`, and a `DbiPanelSpec` supplies the controller specifics.
+
+use crate::dbi::*;
+use crate::dcs::*;
+use crate::error::{BusError, DbiError, ErrorContext, InitStage};
+use crate::otm8009a::defs::{DisplayConfig, Orientation, PowerMode};
+use crate::script::{run_bus_script, write_bus_payload, InitScript};
+use crate::traits::{pixel::Rgb565, *};
+
+/// What sets one DBI panel controller apart from another
+pub trait DbiPanelSpec {
+    /// Size of the panel in landscape orientation
+    fn dimensions(&self) -> (u16, u16);
+
+    /// Vendor register programming up to and including sleep out
+    fn power_up(&self) -> &'static InitScript;
+
+    /// Steps turning the display on once COLMOD and MADCTL are set
+    fn display_on(&self) -> &'static InitScript;
+
+    /// MADCTL value scanning the frame memory in `orientation`
+    fn madctl(&self, orientation: Orientation) -> u8;
+}
+
+/// Driver for a panel with its own frame memory behind a `DisplayBus`, with
+/// the controller specifics taken from `S`
+pub struct DbiPanelDriver<S, B>
+where
+    S: DbiPanelSpec,
+    B: DisplayBus,
+{
+    spec: S,
+    bus: B,
+    width: u16,
+    height: u16,
+    color_format: ColorFormat,
+    initialized: bool,
+    power_mode: PowerMode,
+    /// Stage of `init` running, recorded in bus errors
+    stage: Option<InitStage>,
+}
+
+impl<S, B> DbiPanelDriver<S, B>
+where
+    S: DbiPanelSpec + Default,
+    B: DisplayBus,
+{
+    pub fn new(bus: B) -> Self {
+        let spec = S::default();
+        let (width, height) = spec.dimensions();
+        Self {
+            spec,
+            bus,
+            width: height,
+            height: width,
+            color_format: ColorFormat::Rgb565,
+            initialized: false,
+            power_mode: PowerMode::Off,
+            stage: None,
+        }
+    }
+}
+
+impl<S, B> DbiPanelDriver<S, B>
+where
+    S: DbiPanelSpec,
+    B: DisplayBus,
+{
+    /// Initialize the panel from the raw `*_FORMAT_*` and `*_ORIENTATION_*`
+    /// values
+    pub fn init(&mut self, color_format: u32, orientation: u32) -> Result<(), DbiError<B::Error>> {
+        let orientation = Orientation::from_u32(orientation)
+            .ok_or(DbiError::InvalidConfig)?;
+        let color_format = ColorFormat::from_u32(color_format)
+            .ok_or(DbiError::InvalidConfig)?;
+        self.init_with(DisplayConfig::new(orientation, color_format))
+    }
+
+    /// Initialize the panel and leave it in the power mode of `config`
+    ///
+    /// A config asking for another size than the panel's is rejected.
+    pub fn init_with(&mut self, config: DisplayConfig) -> Result<(), DbiError<B::Error>> {
+        if config.dimensions_on(self.spec.dimensions()).is_none() {
+            return Err(DbiError::InvalidConfig);
+        }
+
+        // The serial interface carries 16-bit and 18-bit pixels only
+        let color_format = config.color_format();
+        if color_format == ColorFormat::Rgb888 {
+            return Err(DbiError::InvalidConfig);
+        }
+
+        let result = self.bring_up(color_format, config.orientation());
+        self.stage = None;
+        result?;
+
+        self.initialized = true;
+        self.power_mode = PowerMode::Normal;
+
+        match config.power_mode() {
+            PowerMode::Normal => Ok(()),
+            PowerMode::Sleep => self.enter_sleep(),
+            mode => self.set_power_mode(mode),
+        }
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        self.initialized
+    }
+
+    pub fn get_dimensions(&self) -> (u16, u16) {
+        (self.width, self.height)
+    }
+
+    pub fn fill_rect(&mut self, x: u16, y: u16, width: u16, height: u16, color: Rgb565) -> Result<(), DbiError<B::Error>> {
+        if !self.initialized {
+            return Err(DbiError::NotReady);
+        }
+
+        if x >= self.width || y >= self.height {
+            return Err(DbiError::InvalidCoordinates);
+        }
+
+        // Clip to the panel like a framebuffer would
+        let width = core::cmp::min(width, self.width - x);
+        let height = core::cmp::min(height, self.height - y);
+        if width == 0 || height == 0 {
+            return Ok(());
+        }
+
+        fill_window(&mut self.bus, x, y, width, height, color, self.color_format)
+            .map_err(DbiError::Bus)
+    }
+
+    pub fn set_pixel(&mut self, x: u16, y: u16, color: Rgb565) -> Result<(), DbiError<B::Error>> {
+        if !self.initialized {
+            return Err(DbiError::NotReady);
+        }
+
+        if x >= self.width || y >= self.height {
+            return Err(DbiError::InvalidCoordinates);
+        }
+
+        let pixel = EncodedPixel::new(color, self.color_format);
+        set_window(&mut self.bus, x, y, x, y)
+            .and_then(|_| write_pixel(&mut self.bus, pixel))
+            .map_err(DbiError::Bus)
+    }
+
+    pub fn clear(&mut self, color: Rgb565) -> Result<(), DbiError<B::Error>> {
+        self.fill_rect(0, 0, self.width, self.height, color)
+    }
+
+    pub fn set_orientation(&mut self, orientation: Orientation) -> Result<(), DbiError<B::Error>> {
+        if !self.initialized {
+            return Err(DbiError::NotReady);
+        }
+
+        self.apply_orientation(orientation)
+    }
+
+    fn apply_orientation(&mut self, orientation: Orientation) -> Result<(), DbiError<B::Error>> {
+        self.write(&[SET_MEMORY_ACCESS_CONTROL, self.spec.madctl(orientation)])?;
+
+        // Update dimensions based on orientation
+        (self.width, self.height) = orientation.dimensions(self.spec.dimensions());
+
+        Ok(())
+    }
+
+    fn bring_up(&mut self, color_format: ColorFormat, orientation: Orientation) -> Result<(), DbiError<B::Error>> {
+        self.run(self.spec.power_up(), InitStage::PowerUp)?;
+
+        // Set color format
+        self.stage = Some(InitStage::PixelFormat);
+        self.write(&[SET_PIXEL_FORMAT, color_format as u8])?;
+        self.bus.delay_ms(timing::CMD_DELAY_MS);
+        self.color_format = color_format;
+
+        // Set orientation
+        self.stage = Some(InitStage::Orientation);
+        self.apply_orientation(orientation)?;
+
+        self.run(self.spec.display_on(), InitStage::DisplayOn)
+    }
+
+    fn run(&mut self, script: &InitScript, stage: fn(u16) -> InitStage) -> Result<(), DbiError<B::Error>> {
+        run_bus_script(&mut self.bus, script)
+            .map_err(|failed| DbiError::Bus(failed.error.during(stage(failed.step as u16))))
+    }
+
+    fn write(&mut self, payload: &[u8]) -> Result<(), DbiError<B::Error>> {
+        let context = ErrorContext { opcode: Some(payload[0]), stage: self.stage };
+        write_bus_payload(&mut self.bus, payload)
+            .map_err(|error| DbiError::Bus(BusError { context, error }))
+    }
+
+    /// Blank the panel and put it to sleep, from any awake power mode
+    pub fn enter_sleep(&mut self) -> Result<(), DbiError<B::Error>> {
+        if !self.initialized {
+            return Err(DbiError::NotReady);
+        }
+
+        // Partial and idle mode would survive sleep
+        if matches!(self.power_mode, PowerMode::PartialDisplay | PowerMode::IdleMode) {
+            self.set_power_mode(PowerMode::Normal)?;
+        }
+        if self.power_mode == PowerMode::Normal {
+            self.set_power_mode(PowerMode::DisplayOff)?;
+        }
+        if self.power_mode == PowerMode::DisplayOff {
+            self.set_power_mode(PowerMode::Sleep)?;
+        }
+        Ok(())
+    }
+
+    /// Wake the panel from sleep or display off
+    pub fn exit_sleep(&mut self) -> Result<(), DbiError<B::Error>> {
+        if !self.initialized {
+            return Err(DbiError::NotReady);
+        }
+
+        if self.power_mode == PowerMode::Sleep {
+            self.set_power_mode(PowerMode::DisplayOff)?;
+        }
+        if self.power_mode == PowerMode::DisplayOff {
+            self.set_power_mode(PowerMode::Normal)?;
+        }
+        Ok(())
+    }
+
+    /// Get the power mode the panel was last put in
+    pub fn power_mode(&self) -> PowerMode {
+        self.power_mode
+    }
+
+    /// Take one step through the power state machine
+    ///
+    /// See [`PowerMode::can_step_to`] for the legal steps. There is no
+    /// platform to cut, so `Off` is never reached.
+    pub fn set_power_mode(&mut self, mode: PowerMode) -> Result<(), DbiError<B::Error>> {
+        if !self.initialized {
+            return Err(DbiError::NotReady);
+        }
+
+        let from = self.power_mode;
+        if from == mode {
+            return Ok(());
+        }
+        if !from.can_step_to(mode) || mode == PowerMode::Off {
+            return Err(DbiError::IllegalTransition { from, to: mode });
+        }
+
+        match (from, mode) {
+            (PowerMode::Sleep, _) => {
+                self.write(&[SLEEP_OUT])?;
+                self.bus.delay_ms(timing::SLEEP_OUT_DELAY_MS);
+            }
+            (_, PowerMode::Sleep) => {
+                self.write(&[SLEEP_IN])?;
+                self.bus.delay_ms(timing::SLEEP_IN_DELAY_MS);
+            }
+            (PowerMode::DisplayOff, _) => {
+                self.write(&[DISPLAY_ON])?;
+                self.bus.delay_ms(timing::DISPLAY_ON_DELAY_MS);
+            }
+            (_, PowerMode::DisplayOff) => {
+                self.write(&[DISPLAY_OFF])?;
+                self.bus.delay_ms(timing::DISPLAY_OFF_DELAY_MS);
+            }
+            (_, PowerMode::PartialDisplay) => {
+                if from == PowerMode::IdleMode {
+                    self.write(&[IDLE_MODE_OFF])?;
+                }
+                self.write(&[PARTIAL_MODE_ON])?;
+            }
+            (_, PowerMode::IdleMode) => {
+                if from == PowerMode::PartialDisplay {
+                    self.write(&[NORMAL_MODE_ON])?;
+                }
+                self.write(&[IDLE_MODE_ON])?;
+            }
+            (PowerMode::PartialDisplay, _) => self.write(&[NORMAL_MODE_ON])?,
+            _ => self.write(&[IDLE_MODE_OFF])?,
+        }
+
+        self.power_mode = mode;
+        Ok(())
+    }
+
+    pub fn set_brightness(&mut self, brightness: u8) -> Result<(), DbiError<B::Error>> {
+        if !self.initialized {
+            return Err(DbiError::NotReady);
+        }
+
+        self.write(&[WRITE_DISPLAY_BRIGHTNESS, brightness])
+    }
+
+    /// Get the controller specifics
+    pub fn spec(&self) -> &S {
+        &self.spec
+    }
+
+    /// Get the display bus
+    pub fn bus(&self) -> &B {
+        &self.bus
+    }
+
+    /// Get mutable access to the display bus
+    pub fn bus_mut(&mut self) -> &mut B {
+        &mut self.bus
+    }
+
+    /// Reset the display
+    pub fn reset(&mut self) -> Result<(), DbiError<B::Error>> {
+        self.write(&[SOFT_RESET])?;
+
+        self.bus.delay_ms(timing::RESET_DELAY_MS);
+        self.initialized = false;
+        self.power_mode = PowerMode::Off;
+
+        Ok(())
+    }
+}
+
+impl<S, B> DisplayPanel for DbiPanelDriver<S, B>
+where
+    S: DbiPanelSpec,
+    B: DisplayBus,
+{
+    type Error = DbiError<B::Error>;
+    type Pixel = Rgb565;
+
+    fn init(&mut self, config: DisplayConfig) -> Result<(), DbiError<B::Error>> {
+        DbiPanelDriver::init_with(self, config)
+    }
+
+    fn is_initialized(&self) -> bool {
+        DbiPanelDriver::is_initialized(self)
+    }
+
+    fn set_orientation(&mut self, orientation: Orientation) -> Result<(), DbiError<B::Error>> {
+        DbiPanelDriver::set_orientation(self, orientation)
+    }
+
+    fn get_dimensions(&self) -> (u16, u16) {
+        DbiPanelDriver::get_dimensions(self)
+    }
+
+    fn fill_rect(&mut self, x: u16, y: u16, width: u16, height: u16, color: Rgb565) -> Result<(), DbiError<B::Error>> {
+        DbiPanelDriver::fill_rect(self, x, y, width, height, color)
+    }
+
+    fn set_pixel(&mut self, x: u16, y: u16, color: Rgb565) -> Result<(), DbiError<B::Error>> {
+        DbiPanelDriver::set_pixel(self, x, y, color)
+    }
+
+    fn clear(&mut self, color: Rgb565) -> Result<(), DbiError<B::Error>> {
+        DbiPanelDriver::clear(self, color)
+    }
+
+    /// Drawing goes straight into the panel's frame memory, so there is
+    /// nothing to push
+    fn flush(&mut self) -> Result<(), DbiError<B::Error>> {
+        if !self.initialized {
+            return Err(DbiError::NotReady);
+        }
+        Ok(())
+    }
+
+    fn power_mode(&self) -> PowerMode {
+        DbiPanelDriver::power_mode(self)
+    }
+
+    fn set_power_mode(&mut self, mode: PowerMode) -> Result<(), DbiError<B::Error>> {
+        DbiPanelDriver::set_power_mode(self, mode)
+    }
+
+    fn enter_sleep(&mut self) -> Result<(), DbiError<B::Error>> {
+        DbiPanelDriver::enter_sleep(self)
+    }
+
+    fn exit_sleep(&mut self) -> Result<(), DbiError<B::Error>> {
+        DbiPanelDriver::exit_sleep(self)
+    }
+
+    fn set_brightness(&mut self, brightness: u8) -> Result<(), DbiError<B::Error>> {
+        DbiPanelDriver::set_brightness(self, brightness)
+    }
+}
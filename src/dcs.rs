@@ -0,0 +1,59 @@
+//! MIPI DCS definitions shared by all panels
+//!
+//! DSI and DBI controllers implement the same Display Command Set for power
+//! modes, frame memory addressing and pixel formats, so both buses take
+//! their standard opcodes from here.
+
+/// Standard DCS commands
+pub const SOFT_RESET: u8 = 0x01;
+pub const SLEEP_IN: u8 = 0x10;
+pub const SLEEP_OUT: u8 = 0x11;
+pub const PARTIAL_MODE_ON: u8 = 0x12;
+pub const NORMAL_MODE_ON: u8 = 0x13;
+pub const DISPLAY_OFF: u8 = 0x28;
+pub const DISPLAY_ON: u8 = 0x29;
+pub const SET_MEMORY_ACCESS_CONTROL: u8 = 0x36;
+pub const IDLE_MODE_OFF: u8 = 0x38;
+pub const IDLE_MODE_ON: u8 = 0x39;
+pub const SET_PIXEL_FORMAT: u8 = 0x3A;
+pub const WRITE_DISPLAY_BRIGHTNESS: u8 = 0x51;
+
+/// Standard DCS commands used to address frame memory
+pub const SET_COLUMN_ADDRESS: u8 = 0x2A;
+pub const SET_PAGE_ADDRESS: u8 = 0x2B;
+pub const WRITE_MEMORY_START: u8 = 0x2C;
+pub const WRITE_MEMORY_CONTINUE: u8 = 0x3C;
+
+/// COLMOD values for the interface pixel formats
+pub const FORMAT_RGB565: u32 = 0x55;
+pub const FORMAT_RGB666: u32 = 0x66;
+pub const FORMAT_RGB888: u32 = 0x77;
+
+/// Interface pixel formats selected with COLMOD
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorFormat {
+    Rgb565 = 0x55,
+    Rgb666 = 0x66,
+    Rgb888 = 0x77,
+}
+
+impl ColorFormat {
+    /// Parse one of the `FORMAT_*` values
+    pub const fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            FORMAT_RGB565 => Some(ColorFormat::Rgb565),
+            FORMAT_RGB666 => Some(ColorFormat::Rgb666),
+            FORMAT_RGB888 => Some(ColorFormat::Rgb888),
+            _ => None,
+        }
+    }
+
+    /// Bytes per pixel in a DCS memory write; 18-bit pixels are sent in the
+    /// upper six bits of three bytes
+    pub const fn dcs_bytes(self) -> usize {
+        match self {
+            ColorFormat::Rgb565 => 2,
+            ColorFormat::Rgb666 | ColorFormat::Rgb888 => 3,
+        }
+    }
+}
@@ -0,0 +1,1137 @@
+//! Generic DSI panel driver
+//!
+//! The OTM8009A and NT35510 share everything above their vendor registers:
+//! the standard DCS commands, the power state machine, the LTDC layers and
+//! the health checks. `DsiPanelDriver` implements that once, and a
+//! `PanelSpec` supplies what sets one controller apart: how it is
+//! identified, its bring-up scripts and the pixel formats it accepts.
+
+use crate::backlight::DynBacklight;
+use crate::common::Fade;
+use crate::error::{DisplayError, DsiError, ErrorContext, InitStage};
+use crate::gram::{write_region, GramError};
+use crate::otm8009a::defs::*;
+use crate::palette::Palette;
+use crate::reset::{pulse_reset, NoPin};
+use crate::script::{run_script, write_payload, InitScript};
+use crate::traits::*;
+
+/// Error of a driver on `D` and `L`, keeping their DSI and LTDC errors
+pub type DriverError<D, L> = DisplayError<<D as DsiInterface>::Error, <L as LtdcInterface>::Error>;
+
+/// What sets one DSI panel controller apart from another
+pub trait PanelSpec {
+    /// RDID register (RDID1..RDID3) identifying the controller
+    fn id_register(&self) -> u8;
+
+    /// Value of `id_register` on this controller
+    fn id(&self) -> u8;
+
+    /// Size of the panel in landscape orientation
+    fn dimensions(&self) -> (u16, u16);
+
+    /// Vendor register programming up to and including sleep out
+    fn power_up(&self) -> &'static InitScript;
+
+    /// Steps turning the display on once COLMOD and MADCTL are set
+    fn display_on(&self) -> &'static InitScript;
+
+    /// Whether the controller has a COLMOD mode for `format`
+    fn supports(&self, format: ColorFormat) -> bool;
+
+    /// Whether `id` was read from this controller
+    fn matches(&self, id: PanelId) -> bool {
+        id.register(self.id_register()) == Some(self.id())
+    }
+}
+
+/// Driver for a DSI panel scanned out by the LTDC, with the controller
+/// specifics taken from `S`
+pub struct DsiPanelDriver<S, D, L, F, R = NoPin>
+where
+    S: PanelSpec,
+    D: DsiInterface,
+    L: LtdcInterface,
+    F: FramebufferInterface,
+    R: OutputPin,
+{
+    spec: S,
+    dsi: D,
+    ltdc: L,
+    framebuffer: F,
+    width: u16,
+    height: u16,
+    orientation: Orientation,
+    color_format: ColorFormat,
+    initialized: bool,
+    refresh_mode: RefreshMode,
+    tear_mode: Option<TearMode>,
+    layers: [Option<LayerConfig>; LTDC_LAYER_COUNT as usize],
+    power_mode: PowerMode,
+    /// Rows last set with `set_partial_area`, kept to restore after a reset
+    partial_area: Option<(u16, u16)>,
+    brightness: u8,
+    backlight: Option<&'static mut dyn DynBacklight>,
+    reset_pin: Option<R>,
+    /// Stage of `init` running, recorded in DSI errors
+    stage: Option<InitStage>,
+}
+
+impl<S, D, L, F> DsiPanelDriver<S, D, L, F>
+where
+    S: PanelSpec + Default,
+    D: DsiInterface,
+    L: LtdcInterface,
+    F: FramebufferInterface,
+{
+    pub fn new(dsi: D, ltdc: L, framebuffer: F) -> Self {
+        Self::build(S::default(), dsi, ltdc, framebuffer, None)
+    }
+}
+
+impl<S, D, L, F> DsiPanelDriver<S, D, L, F>
+where
+    S: PanelSpec,
+    D: DsiInterface,
+    L: LtdcInterface,
+    F: FramebufferInterface,
+{
+    /// Toggle the panel's XRES line through `reset_pin` on `reset` and
+    /// before `init`
+    pub fn with_reset_pin<R: OutputPin>(self, reset_pin: R) -> DsiPanelDriver<S, D, L, F, R> {
+        DsiPanelDriver {
+            spec: self.spec,
+            dsi: self.dsi,
+            ltdc: self.ltdc,
+            framebuffer: self.framebuffer,
+            width: self.width,
+            height: self.height,
+            orientation: self.orientation,
+            color_format: self.color_format,
+            initialized: self.initialized,
+            refresh_mode: self.refresh_mode,
+            tear_mode: self.tear_mode,
+            layers: self.layers,
+            power_mode: self.power_mode,
+            partial_area: self.partial_area,
+            brightness: self.brightness,
+            backlight: self.backlight,
+            reset_pin: Some(reset_pin),
+            stage: self.stage,
+        }
+    }
+}
+
+impl<S, D, L, F, R> DsiPanelDriver<S, D, L, F, R>
+where
+    S: PanelSpec + Default,
+    D: DsiInterface,
+    L: LtdcInterface,
+    F: FramebufferInterface,
+    R: OutputPin,
+{
+    /// Create a driver for a board that drives the backlight itself, e.g.
+    /// with PWM; brightness then goes to `backlight` instead of the panel.
+    /// The backlight is lent for good, typically from a `static`.
+    pub fn with_backlight(dsi: D, ltdc: L, framebuffer: F, backlight: &'static mut impl Backlight) -> Self {
+        Self::build(S::default(), dsi, ltdc, framebuffer, Some(backlight))
+    }
+}
+
+impl<S, D, L, F, R> DsiPanelDriver<S, D, L, F, R>
+where
+    S: PanelSpec,
+    D: DsiInterface,
+    L: LtdcInterface,
+    F: FramebufferInterface,
+    R: OutputPin,
+{
+    /// Create a driver for the controller described by `spec`
+    pub(crate) fn build(spec: S, dsi: D, ltdc: L, framebuffer: F, backlight: Option<&'static mut dyn DynBacklight>) -> Self {
+        let (width, height) = spec.dimensions();
+        Self {
+            spec,
+            dsi,
+            ltdc,
+            framebuffer,
+            width,
+            height,
+            orientation: Orientation::Landscape,
+            color_format: ColorFormat::Rgb565,
+            initialized: false,
+            refresh_mode: RefreshMode::Video,
+            tear_mode: None,
+            layers: Default::default(),
+            power_mode: PowerMode::Off,
+            partial_area: None,
+            brightness: init_sequences::CMD_DISPLAY_BRIGHTNESS[1],
+            backlight,
+            reset_pin: None,
+            stage: None,
+        }
+    }
+
+    /// Initialize from the raw `*_FORMAT_*` and `*_ORIENTATION_*` values
+    pub fn init(&mut self, color_format: u32, orientation: u32) -> Result<(), DriverError<D, L>> {
+        let orientation = Orientation::from_u32(orientation)
+            .ok_or(DisplayError::InvalidConfig)?;
+        let color_format = ColorFormat::from_u32(color_format)
+            .ok_or(DisplayError::InvalidConfig)?;
+        self.init_with(DisplayConfig::new(orientation, color_format))
+    }
+
+    /// Initialize the panel and leave it in the power mode of `config`
+    ///
+    /// The config must fit the panel, and the framebuffer must be exactly
+    /// the panel size in the configured orientation, otherwise the LTDC
+    /// would scan out past it.
+    pub fn init_with(&mut self, config: DisplayConfig) -> Result<(), DriverError<D, L>> {
+        let dimensions = config.dimensions_on(self.spec.dimensions());
+        if dimensions.is_none() || dimensions != Some(self.framebuffer.get_dimensions()) {
+            return Err(DisplayError::InvalidConfig);
+        }
+
+        // Start from a freshly reset panel when XRES is wired
+        if self.reset_pin.is_some() {
+            self.pulse_reset_pin()?;
+        }
+
+        // Initialize the display controller
+        self.init_panel(config.color_format(), config.orientation())?;
+        self.color_format = config.color_format();
+        
+        // Configure the LTDC layer
+        let layer_config = LayerConfig::new(0, &self.framebuffer, self.width, self.height);
+        self.apply_layer(layer_config)?;
+        
+        // Set framebuffer address
+        let fb_addr = self.framebuffer.get_buffer_ptr() as u32;
+        self.ltdc.set_framebuffer(0, fb_addr)
+            .map_err(DisplayError::Ltdc)?;
+        
+        // Enable LTDC
+        self.ltdc.enable()
+            .map_err(DisplayError::Ltdc)?;
+        
+        self.initialized = true;
+        self.power_mode = PowerMode::Normal;
+        self.partial_area = None;
+
+        // The panel came up at the default brightness
+        if self.backlight.is_some() {
+            self.set_brightness(self.brightness)?;
+            self.set_backlight_enabled(true)?;
+        }
+
+        match config.power_mode() {
+            PowerMode::Normal => Ok(()),
+            PowerMode::Sleep => self.enter_sleep(),
+            mode => self.set_power_mode(mode),
+        }
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        self.initialized
+    }
+
+    pub fn get_dimensions(&self) -> (u16, u16) {
+        (self.width, self.height)
+    }
+
+    pub fn fill_rect(&mut self, x: u16, y: u16, width: u16, height: u16, color: F::Pixel) -> Result<(), DriverError<D, L>> {
+        if !self.initialized {
+            return Err(DisplayError::NotReady);
+        }
+        
+        if x >= self.width || y >= self.height {
+            return Err(DisplayError::InvalidCoordinates);
+        }
+        
+        self.framebuffer.fill_rect(x, y, width, height, color);
+        Ok(())
+    }
+
+    pub fn set_pixel(&mut self, x: u16, y: u16, color: F::Pixel) -> Result<(), DriverError<D, L>> {
+        if !self.initialized {
+            return Err(DisplayError::NotReady);
+        }
+        
+        if x >= self.width || y >= self.height {
+            return Err(DisplayError::InvalidCoordinates);
+        }
+        
+        self.framebuffer.set_pixel(x, y, color);
+        Ok(())
+    }
+
+    pub fn clear(&mut self, color: F::Pixel) -> Result<(), DriverError<D, L>> {
+        if !self.initialized {
+            return Err(DisplayError::NotReady);
+        }
+        
+        self.framebuffer.clear(color);
+        Ok(())
+    }
+
+    /// Rotate or flip the display
+    ///
+    /// Layer 0 keeps scanning out the framebuffer, so only orientations with
+    /// the framebuffer's dimensions are accepted, e.g. a landscape buffer can
+    /// be flipped but not turned to portrait.
+    pub fn set_orientation(&mut self, orientation: Orientation) -> Result<(), DriverError<D, L>> {
+        if !self.initialized {
+            return Err(DisplayError::NotReady);
+        }
+
+        if self.framebuffer.get_dimensions() != orientation.dimensions(self.spec.dimensions()) {
+            return Err(DisplayError::InvalidConfig);
+        }
+
+        self.apply_orientation(orientation)
+    }
+
+    fn apply_orientation(&mut self, orientation: Orientation) -> Result<(), DriverError<D, L>> {
+        let (cmd, caset, paset) = match orientation {
+            Orientation::Portrait => (
+                &init_sequences::CMD_PORTRAIT,
+                &init_sequences::CMD_CASET_PORTRAIT,
+                &init_sequences::CMD_PASET_PORTRAIT,
+            ),
+            Orientation::Landscape => (
+                &init_sequences::CMD_LANDSCAPE,
+                &init_sequences::CMD_CASET_LANDSCAPE,
+                &init_sequences::CMD_PASET_LANDSCAPE,
+            ),
+            Orientation::PortraitFlipped => (
+                &init_sequences::CMD_PORTRAIT_FLIPPED,
+                &init_sequences::CMD_CASET_PORTRAIT,
+                &init_sequences::CMD_PASET_PORTRAIT,
+            ),
+            Orientation::LandscapeFlipped => (
+                &init_sequences::CMD_LANDSCAPE_FLIPPED,
+                &init_sequences::CMD_CASET_LANDSCAPE,
+                &init_sequences::CMD_PASET_LANDSCAPE,
+            ),
+        };
+        
+        // Set orientation, column and page address
+        self.write(cmd)?;
+        self.write(caset)?;
+        self.write(paset)?;
+        
+        // Update dimensions based on orientation
+        (self.width, self.height) = orientation.dimensions(self.spec.dimensions());
+        self.orientation = orientation;
+        
+        Ok(())
+    }
+
+    fn init_panel(&mut self, color_format: ColorFormat, orientation: Orientation) -> Result<(), DriverError<D, L>> {
+        let result = self.bring_up(color_format, orientation);
+        self.stage = None;
+        result
+    }
+
+    fn bring_up(&mut self, color_format: ColorFormat, orientation: Orientation) -> Result<(), DriverError<D, L>> {
+        // Make sure the expected controller is attached before programming
+        // vendor registers
+        self.stage = Some(InitStage::ReadId);
+        let id = self.read_id()?;
+        let value = id.register(self.spec.id_register()).unwrap_or_default();
+        if value != self.spec.id() {
+            return Err(DisplayError::WrongPanelId(value));
+        }
+
+        if !self.spec.supports(color_format) {
+            return Err(DisplayError::InvalidConfig);
+        }
+
+        self.run(self.spec.power_up(), InitStage::PowerUp)?;
+
+        // Set color format; the ColorFormat values are the COLMOD codes
+        self.stage = Some(InitStage::PixelFormat);
+        self.write(&[commands::SET_PIXEL_FORMAT, color_format as u8])?;
+        self.dsi.delay_ms(timing::CMD_DELAY_MS);
+
+        // Set orientation
+        self.stage = Some(InitStage::Orientation);
+        self.apply_orientation(orientation)?;
+
+        self.run(self.spec.display_on(), InitStage::DisplayOn)
+    }
+
+    fn run(&mut self, script: &InitScript, stage: fn(u16) -> InitStage) -> Result<(), DriverError<D, L>> {
+        run_script(&mut self.dsi, script)
+            .map_err(|failed| DisplayError::Dsi(failed.error.during(stage(failed.step as u16))))
+    }
+
+    fn write(&mut self, payload: &[u8]) -> Result<(), DriverError<D, L>> {
+        let context = ErrorContext { opcode: Some(payload[0]), stage: self.stage };
+        write_payload(&mut self.dsi, payload)
+            .map_err(|error| DisplayError::Dsi(DsiError { context, error }))
+    }
+
+    /// Blank the panel and put it to sleep, from any awake power mode
+    pub fn enter_sleep(&mut self) -> Result<(), DriverError<D, L>> {
+        if !self.initialized {
+            return Err(DisplayError::NotReady);
+        }
+
+        // Partial and idle mode would survive sleep
+        if matches!(self.power_mode, PowerMode::PartialDisplay | PowerMode::IdleMode) {
+            self.set_power_mode(PowerMode::Normal)?;
+        }
+        if self.power_mode == PowerMode::Normal {
+            self.set_power_mode(PowerMode::DisplayOff)?;
+        }
+        if self.power_mode == PowerMode::DisplayOff {
+            self.set_power_mode(PowerMode::Sleep)?;
+        }
+        Ok(())
+    }
+
+    /// Wake the panel from sleep or display off
+    pub fn exit_sleep(&mut self) -> Result<(), DriverError<D, L>> {
+        if !self.initialized {
+            return Err(DisplayError::NotReady);
+        }
+
+        if self.power_mode == PowerMode::Sleep {
+            self.set_power_mode(PowerMode::DisplayOff)?;
+        }
+        if self.power_mode == PowerMode::DisplayOff {
+            self.set_power_mode(PowerMode::Normal)?;
+        }
+        match self.power_mode {
+            PowerMode::Off => Err(DisplayError::IllegalTransition { from: PowerMode::Off, to: PowerMode::Normal }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Get the power mode the panel was last put in
+    pub fn power_mode(&self) -> PowerMode {
+        self.power_mode
+    }
+
+    /// Take one step through the power state machine
+    ///
+    /// See [`PowerMode::can_step_to`] for the legal steps. Each step waits
+    /// out the delay the datasheet requires before the next command, and the
+    /// LTDC only streams while the display is on. `Off` is entered and left
+    /// with `power_off` and `power_on`, which also handle the platform.
+    pub fn set_power_mode(&mut self, mode: PowerMode) -> Result<(), DriverError<D, L>> {
+        if !self.initialized {
+            return Err(DisplayError::NotReady);
+        }
+
+        let from = self.power_mode;
+        if from == mode {
+            return Ok(());
+        }
+        if !from.can_step_to(mode) || from == PowerMode::Off || mode == PowerMode::Off {
+            return Err(DisplayError::IllegalTransition { from, to: mode });
+        }
+
+        match (from, mode) {
+            (PowerMode::Sleep, _) => {
+                self.write(&single_commands::SLEEP_OUT)?;
+                self.dsi.delay_ms(timing::SLEEP_OUT_DELAY_MS);
+            }
+            (_, PowerMode::Sleep) => {
+                self.write(&single_commands::SLEEP_IN)?;
+                self.dsi.delay_ms(timing::SLEEP_IN_DELAY_MS);
+            }
+            (PowerMode::DisplayOff, _) => {
+                // Stream valid frames before the panel shows them
+                self.ltdc.enable()
+                    .map_err(DisplayError::Ltdc)?;
+                self.write(&single_commands::DISPLAY_ON)?;
+                self.dsi.delay_ms(timing::DISPLAY_ON_DELAY_MS);
+                self.set_backlight_enabled(true)?;
+            }
+            (_, PowerMode::DisplayOff) => {
+                self.set_backlight_enabled(false)?;
+                self.write(&single_commands::DISPLAY_OFF)?;
+                self.dsi.delay_ms(timing::DISPLAY_OFF_DELAY_MS);
+                self.ltdc.disable()
+                    .map_err(DisplayError::Ltdc)?;
+            }
+            (_, PowerMode::PartialDisplay) => {
+                if from == PowerMode::IdleMode {
+                    self.write(&[commands::IDLE_MODE_OFF])?;
+                }
+                self.write(&[commands::PARTIAL_MODE_ON])?;
+            }
+            (_, PowerMode::IdleMode) => {
+                if from == PowerMode::PartialDisplay {
+                    self.write(&[commands::NORMAL_MODE_ON])?;
+                }
+                self.write(&[commands::IDLE_MODE_ON])?;
+            }
+            (PowerMode::PartialDisplay, _) => self.write(&[commands::NORMAL_MODE_ON])?,
+            _ => self.write(&[commands::IDLE_MODE_OFF])?,
+        }
+
+        self.power_mode = mode;
+        Ok(())
+    }
+
+    /// Put the panel to sleep, then let the platform cut DSI and LTDC clocks
+    pub fn power_off<P: PlatformInterface>(&mut self, platform: &mut P) -> Result<(), DriverError<D, L>> {
+        self.enter_sleep()?;
+        platform.enter_low_power()
+            .map_err(|_| DisplayError::CommError)?;
+
+        self.power_mode = PowerMode::Off;
+        Ok(())
+    }
+
+    /// Bring the platform out of low power, leaving the panel asleep
+    ///
+    /// Follow with `exit_sleep` to show the display again.
+    pub fn power_on<P: PlatformInterface>(&mut self, platform: &mut P) -> Result<(), DriverError<D, L>> {
+        if !self.initialized {
+            return Err(DisplayError::NotReady);
+        }
+
+        if self.power_mode != PowerMode::Off {
+            return Err(DisplayError::IllegalTransition { from: self.power_mode, to: PowerMode::Sleep });
+        }
+
+        platform.exit_low_power()
+            .map_err(|_| DisplayError::CommError)?;
+
+        self.power_mode = PowerMode::Sleep;
+        Ok(())
+    }
+
+    /// Set the rows refreshed in partial mode
+    pub fn set_partial_area(&mut self, start: u16, end: u16) -> Result<(), DriverError<D, L>> {
+        if !self.initialized {
+            return Err(DisplayError::NotReady);
+        }
+
+        if start > end || end >= self.height {
+            return Err(DisplayError::InvalidConfig);
+        }
+
+        let [start_high, start_low] = start.to_be_bytes();
+        let [end_high, end_low] = end.to_be_bytes();
+        self.write(&[commands::SET_PARTIAL_AREA, start_high, start_low, end_high, end_low])?;
+        self.partial_area = Some((start, end));
+        Ok(())
+    }
+
+    /// Only refresh rows `start..=end` and show black elsewhere, e.g. for an
+    /// always-on status line
+    pub fn enter_partial_mode(&mut self, start: u16, end: u16) -> Result<(), DriverError<D, L>> {
+        if !self.initialized {
+            return Err(DisplayError::NotReady);
+        }
+
+        // Moving the partial area is always allowed
+        let from = self.power_mode;
+        if from != PowerMode::PartialDisplay && !from.can_step_to(PowerMode::PartialDisplay) {
+            return Err(DisplayError::IllegalTransition { from, to: PowerMode::PartialDisplay });
+        }
+
+        self.set_partial_area(start, end)?;
+        self.set_power_mode(PowerMode::PartialDisplay)
+    }
+
+    /// Reduce the panel to 8 colors
+    pub fn enter_idle_mode(&mut self) -> Result<(), DriverError<D, L>> {
+        self.set_power_mode(PowerMode::IdleMode)
+    }
+
+    /// Leave partial or idle mode, or turn the display back on
+    pub fn enter_normal_mode(&mut self) -> Result<(), DriverError<D, L>> {
+        self.set_power_mode(PowerMode::Normal)
+    }
+
+    /// Write the display brightness register (0x51)
+    pub fn set_brightness(&mut self, brightness: u8) -> Result<(), DriverError<D, L>> {
+        if !self.initialized {
+            return Err(DisplayError::NotReady);
+        }
+
+        match self.backlight.as_mut() {
+            Some(backlight) => {
+                let level = backlight.perceptual_level(brightness);
+                backlight.set_level(level)
+                    .map_err(|_| DisplayError::CommError)?;
+            }
+            None => self.write(&[commands::WRITE_DISPLAY_BRIGHTNESS, brightness])?,
+        }
+        self.brightness = brightness;
+        Ok(())
+    }
+
+    fn set_backlight_enabled(&mut self, enabled: bool) -> Result<(), DriverError<D, L>> {
+        match self.backlight.as_mut() {
+            Some(backlight) => backlight.set_enabled(enabled)
+                .map_err(|_| DisplayError::CommError),
+            None => Ok(()),
+        }
+    }
+
+    /// Get the brightness last written to the panel
+    pub fn brightness(&self) -> u8 {
+        self.brightness
+    }
+
+    /// Ramp the brightness to `brightness` over about `duration_ms`
+    pub fn fade_brightness(&mut self, brightness: u8, duration_ms: u32) -> Result<(), DriverError<D, L>> {
+        if !self.initialized {
+            return Err(DisplayError::NotReady);
+        }
+
+        let fade = Fade::new(self.brightness, brightness, duration_ms);
+        let step_ms = fade.step_ms();
+        for (step, level) in fade.enumerate() {
+            if step > 0 {
+                self.dsi.delay_ms(step_ms);
+            }
+            self.set_brightness(level)?;
+        }
+        Ok(())
+    }
+
+    /// Select the backlight control bits (0x53)
+    pub fn set_ctrl_display(&mut self, ctrl: CtrlDisplay) -> Result<(), DriverError<D, L>> {
+        if !self.initialized {
+            return Err(DisplayError::NotReady);
+        }
+
+        self.write(&[commands::WRITE_CTRL_DISPLAY, ctrl.bits()])
+    }
+
+    /// Select the content adaptive backlight control mode (0x55)
+    pub fn set_cabc_mode(&mut self, mode: CabcMode) -> Result<(), DriverError<D, L>> {
+        if !self.initialized {
+            return Err(DisplayError::NotReady);
+        }
+
+        self.write(&[commands::WRITE_CABC, mode as u8])
+    }
+
+    pub fn disable_cabc(&mut self) -> Result<(), DriverError<D, L>> {
+        self.set_cabc_mode(CabcMode::Off)
+    }
+
+    /// Set the lowest brightness CABC may dim to (0x5E)
+    pub fn set_cabc_min_brightness(&mut self, brightness: u8) -> Result<(), DriverError<D, L>> {
+        if !self.initialized {
+            return Err(DisplayError::NotReady);
+        }
+
+        self.write(&[commands::WRITE_CABC_MIN_BRIGHTNESS, brightness])
+    }
+
+    /// Get the framebuffer interface for direct pixel operations
+    pub fn framebuffer(&self) -> &F {
+        &self.framebuffer
+    }
+
+    /// Get mutable access to the framebuffer interface
+    pub fn framebuffer_mut(&mut self) -> &mut F {
+        &mut self.framebuffer
+    }
+
+    /// Get the spec of the driven controller
+    pub fn spec(&self) -> &S {
+        &self.spec
+    }
+
+    /// Get the DSI interface
+    pub fn dsi(&self) -> &D {
+        &self.dsi
+    }
+
+    /// Get mutable access to the DSI interface
+    pub fn dsi_mut(&mut self) -> &mut D {
+        &mut self.dsi
+    }
+
+    /// Get the LTDC interface
+    pub fn ltdc(&self) -> &L {
+        &self.ltdc
+    }
+
+    /// Re-latch the framebuffer address so the LTDC picks up the latest contents
+    pub fn update(&mut self) -> Result<(), DriverError<D, L>> {
+        if !self.initialized {
+            return Err(DisplayError::NotReady);
+        }
+
+        let fb_addr = self.framebuffer.get_buffer_ptr() as u32;
+        self.ltdc.set_framebuffer(0, fb_addr)
+            .map_err(DisplayError::Ltdc)?;
+        if let Some(config) = &mut self.layers[0] {
+            config.framebuffer_address = fb_addr;
+        }
+
+        Ok(())
+    }
+
+    /// Copy a dirty rectangle of the framebuffer into panel GRAM
+    ///
+    /// For DSI command mode, where the panel refreshes from its own memory
+    /// instead of the LTDC video stream.
+    pub fn flush_region(&mut self, x: u16, y: u16, width: u16, height: u16) -> Result<(), DriverError<D, L>>
+    where
+        F::Pixel: DcsPixel,
+    {
+        if !self.initialized {
+            return Err(DisplayError::NotReady);
+        }
+
+        if x >= self.width || y >= self.height {
+            return Err(DisplayError::InvalidCoordinates);
+        }
+
+        write_region(&mut self.dsi, &self.framebuffer, self.color_format, x, y, width, height)
+            .map_err(|error| match error {
+                GramError::InvalidConfig => DisplayError::InvalidConfig,
+                GramError::Dsi(error) => DisplayError::Dsi(error),
+            })
+    }
+
+    /// Select how the panel is refreshed, which decides what `present` does
+    pub fn set_refresh_mode(&mut self, mode: RefreshMode) {
+        self.refresh_mode = mode;
+    }
+
+    pub fn refresh_mode(&self) -> RefreshMode {
+        self.refresh_mode
+    }
+
+    /// Turn on the panel's tearing effect output
+    pub fn enable_tearing_effect(&mut self, mode: TearMode) -> Result<(), DriverError<D, L>> {
+        if !self.initialized {
+            return Err(DisplayError::NotReady);
+        }
+
+        self.write(&[commands::SET_TEAR_ON, mode as u8])?;
+        self.tear_mode = Some(mode);
+        Ok(())
+    }
+
+    pub fn disable_tearing_effect(&mut self) -> Result<(), DriverError<D, L>> {
+        if !self.initialized {
+            return Err(DisplayError::NotReady);
+        }
+
+        self.write(&[commands::SET_TEAR_OFF])?;
+        self.tear_mode = None;
+        Ok(())
+    }
+
+    /// Get the tearing effect output, if enabled
+    pub fn tear_mode(&self) -> Option<TearMode> {
+        self.tear_mode
+    }
+
+    /// Pulse the tearing effect line when the panel refreshes `line` instead
+    /// of at the start of vertical blanking
+    pub fn set_tear_scanline(&mut self, line: u16) -> Result<(), DriverError<D, L>> {
+        if !self.initialized {
+            return Err(DisplayError::NotReady);
+        }
+
+        let [high, low] = line.to_be_bytes();
+        self.write(&[commands::SET_TEAR_SCANLINE, high, low])
+    }
+
+    /// Show the framebuffer contents without tearing
+    ///
+    /// In video mode the framebuffer address is re-latched at the start of
+    /// vertical blanking. In command mode the framebuffer is copied into GRAM
+    /// once the panel signals a tearing effect, so `enable_tearing_effect`
+    /// must be called first.
+    pub fn present(&mut self) -> Result<(), DriverError<D, L>>
+    where
+        F::Pixel: DcsPixel,
+    {
+        self.present_region(0, 0, self.width, self.height)
+    }
+
+    /// Like `present`, but only copies a dirty rectangle in command mode
+    pub fn present_region(&mut self, x: u16, y: u16, width: u16, height: u16) -> Result<(), DriverError<D, L>>
+    where
+        F::Pixel: DcsPixel,
+    {
+        if !self.initialized {
+            return Err(DisplayError::NotReady);
+        }
+
+        match self.refresh_mode {
+            RefreshMode::Video => {
+                self.ltdc.wait_for_vblank()
+                    .map_err(DisplayError::Ltdc)?;
+                self.update()
+            }
+            RefreshMode::Command => {
+                if self.tear_mode.is_none() {
+                    return Err(DisplayError::InvalidConfig);
+                }
+
+                self.dsi.wait_for_tearing_effect()
+                    .map_err(|error| DisplayError::Dsi(DsiError::host(error)))?;
+                self.flush_region(x, y, width, height)
+            }
+        }
+    }
+
+    /// Show the back buffer and start drawing into the next one
+    ///
+    /// The new layer address takes effect on the next LTDC reload. With only
+    /// two buffers the old front buffer is scanned out until then, so this
+    /// waits for vertical blanking before it can be drawn into.
+    pub fn flip(&mut self) -> Result<(), DriverError<D, L>>
+    where
+        F: PageFlip,
+    {
+        if !self.initialized {
+            return Err(DisplayError::NotReady);
+        }
+
+        self.framebuffer.flip();
+
+        let fb_addr = self.framebuffer.get_buffer_ptr() as u32;
+        self.ltdc.set_framebuffer(0, fb_addr)
+            .map_err(DisplayError::Ltdc)?;
+        if let Some(config) = &mut self.layers[0] {
+            config.framebuffer_address = fb_addr;
+        }
+
+        if self.framebuffer.buffer_count() < 3 {
+            self.ltdc.wait_for_vblank()
+                .map_err(DisplayError::Ltdc)?;
+        }
+
+        self.framebuffer.restore_back();
+        Ok(())
+    }
+
+    /// Get the configuration of an LTDC layer, if it has been configured
+    pub fn layer_config(&self, layer: u8) -> Option<&LayerConfig> {
+        self.layers.get(layer as usize)?.as_ref()
+    }
+
+    /// Configure an LTDC layer, e.g. an overlay on layer 1 above the
+    /// framebuffer on layer 0
+    pub fn configure_layer(&mut self, config: LayerConfig) -> Result<(), DriverError<D, L>> {
+        if !self.initialized {
+            return Err(DisplayError::NotReady);
+        }
+
+        self.apply_layer(config)
+    }
+
+    /// Change a configured layer's window, framebuffer, alpha, blending
+    /// factors, default color or color key
+    pub fn update_layer(&mut self, layer: u8, update: impl FnOnce(&mut LayerConfig)) -> Result<(), DriverError<D, L>> {
+        if !self.initialized {
+            return Err(DisplayError::NotReady);
+        }
+
+        let mut config = self.layer_config(layer).cloned().ok_or(DisplayError::InvalidConfig)?;
+        update(&mut config);
+        config.layer = layer;
+        self.apply_layer(config)
+    }
+
+    /// Show a configured layer
+    pub fn enable_layer(&mut self, layer: u8) -> Result<(), DriverError<D, L>> {
+        self.set_layer_enabled(layer, true)
+    }
+
+    /// Hide a layer, keeping its configuration
+    pub fn disable_layer(&mut self, layer: u8) -> Result<(), DriverError<D, L>> {
+        self.set_layer_enabled(layer, false)
+    }
+
+    /// Load the color lookup table of a layer with an indexed pixel format
+    pub fn load_palette(&mut self, layer: u8, palette: &Palette) -> Result<(), DriverError<D, L>> {
+        if !self.initialized {
+            return Err(DisplayError::NotReady);
+        }
+
+        let config = self.layer_config(layer).ok_or(DisplayError::InvalidConfig)?;
+        if palette.len() > config.pixel_format.clut_size() {
+            return Err(DisplayError::InvalidConfig);
+        }
+
+        self.ltdc.load_clut(layer, palette.as_words())
+            .map_err(DisplayError::Ltdc)
+    }
+
+    fn set_layer_enabled(&mut self, layer: u8, enabled: bool) -> Result<(), DriverError<D, L>> {
+        if !self.initialized {
+            return Err(DisplayError::NotReady);
+        }
+
+        let config = self.layers.get_mut(layer as usize)
+            .and_then(Option::as_mut)
+            .ok_or(DisplayError::InvalidConfig)?;
+
+        if enabled {
+            self.ltdc.enable_layer(layer)
+        } else {
+            self.ltdc.disable_layer(layer)
+        }.map_err(DisplayError::Ltdc)?;
+
+        config.enabled = enabled;
+        Ok(())
+    }
+
+    fn apply_layer(&mut self, config: LayerConfig) -> Result<(), DriverError<D, L>> {
+        if !config.is_valid(self.width, self.height) {
+            return Err(DisplayError::InvalidConfig);
+        }
+
+        self.ltdc.configure_layer(config.layer, config.clone())
+            .map_err(DisplayError::Ltdc)?;
+        let index = config.layer as usize;
+        self.layers[index] = Some(config);
+        Ok(())
+    }
+
+    /// Read the panel identification registers
+    pub fn read_id(&mut self) -> Result<PanelId, DriverError<D, L>> {
+        Ok(PanelId {
+            manufacturer: self.read_u8(commands::READ_ID1)?,
+            version: self.read_u8(commands::READ_ID2)?,
+            driver: self.read_u8(commands::READ_ID3)?,
+        })
+    }
+
+    /// Read the power mode register (booster, idle, partial, sleep out, display on bits)
+    pub fn read_power_mode(&mut self) -> Result<u8, DriverError<D, L>> {
+        self.read_u8(commands::GET_POWER_MODE)
+    }
+
+    /// Read the current MADCTL value
+    pub fn read_madctl(&mut self) -> Result<u8, DriverError<D, L>> {
+        self.read_u8(commands::GET_ADDRESS_MODE)
+    }
+
+    /// Read the current COLMOD value
+    pub fn read_pixel_format(&mut self) -> Result<u8, DriverError<D, L>> {
+        self.read_u8(commands::GET_PIXEL_FORMAT)
+    }
+
+    /// Read the self-diagnostic register (0x0F)
+    pub fn read_diagnostic(&mut self) -> Result<u8, DriverError<D, L>> {
+        self.read_u8(commands::GET_DIAGNOSTIC_RESULT)
+    }
+
+    /// Read the power mode and self-diagnostic registers
+    pub fn read_status(&mut self) -> Result<PanelStatus, DriverError<D, L>> {
+        Ok(PanelStatus {
+            power_mode: self.read_power_mode()?,
+            diagnostic: self.read_diagnostic()?,
+        })
+    }
+
+    fn read_u8(&mut self, command: u8) -> Result<u8, DriverError<D, L>> {
+        let mut value = [0u8; 1];
+        let context = ErrorContext { opcode: Some(command), stage: self.stage };
+        self.dsi.read_register(command, &mut value)
+            .map_err(|error| DisplayError::Dsi(DsiError { context, error }))?;
+        Ok(value[0])
+    }
+
+    /// Check if the DSI interface is ready
+    pub fn is_dsi_ready(&self) -> bool {
+        self.dsi.is_ready()
+    }
+
+    /// Reset the display
+    ///
+    /// Resets the DSI host, then pulses XRES if the driver has a reset pin.
+    /// The driver must be initialized again afterwards; the power mode is
+    /// read back from the panel, or assumed to be `Sleep` if it does not
+    /// answer.
+    pub fn reset(&mut self) -> Result<(), DriverError<D, L>> {
+        self.reset_host()?;
+        
+        if self.reset_pin.is_some() {
+            self.pulse_reset_pin()?;
+        } else {
+            self.dsi.delay_ms(timing::RESET_DELAY_MS);
+        }
+        self.initialized = false;
+        self.tear_mode = None;
+        self.power_mode = self.read_power_mode()
+            .map_or(PowerMode::Sleep, PowerMode::from_status);
+        
+        Ok(())
+    }
+
+    /// Check that the panel is still showing the display, and recover it if
+    /// not
+    ///
+    /// ESD can reset the panel into sleep while the LTDC keeps streaming, so
+    /// the screen stays blank, or corrupt its registers. If the driver has the
+    /// display on but the panel reports sleep or display off, fails its
+    /// self-diagnostic, or does not answer, `report` gets `HealthEvent::Lost`,
+    /// the panel is brought back with `recover` and `report` gets
+    /// `HealthEvent::Recovered`. Returns the status read last.
+    pub fn check_health(&mut self, mut report: impl FnMut(HealthEvent)) -> Result<PanelStatus, DriverError<D, L>> {
+        if !self.initialized {
+            return Err(DisplayError::NotReady);
+        }
+
+        // Nothing to lose while the display is meant to be off
+        let expected = self.power_mode;
+        if !expected.is_display_on() {
+            return self.read_status();
+        }
+
+        let status = match self.read_status() {
+            Ok(status) if status.is_healthy() => return Ok(status),
+            Ok(status) => Some(status),
+            Err(DisplayError::Dsi(_)) => None,
+            Err(error) => return Err(error),
+        };
+
+        report(HealthEvent::Lost { expected, status });
+        self.recover()?;
+        let status = self.read_status()?;
+        report(HealthEvent::Recovered(status));
+        Ok(status)
+    }
+
+    /// Reset and initialize the panel again, restoring the orientation,
+    /// color format, brightness, tearing effect, LTDC layers, partial area
+    /// and power mode
+    ///
+    /// In command mode the panel's GRAM is lost, so `present` the framebuffer
+    /// again. A panel in `Off` needs the platform and is not recovered.
+    pub fn recover(&mut self) -> Result<(), DriverError<D, L>> {
+        if !self.initialized {
+            return Err(DisplayError::NotReady);
+        }
+
+        let power_mode = self.power_mode;
+        if power_mode == PowerMode::Off {
+            return Err(DisplayError::IllegalTransition { from: PowerMode::Off, to: PowerMode::Normal });
+        }
+
+        let layers = self.layers.clone();
+        let brightness = self.brightness;
+        let tear_mode = self.tear_mode;
+        let partial_area = self.partial_area;
+
+        // init_with pulses XRES itself, so only the host is reset here
+        self.reset_host()?;
+        if self.reset_pin.is_none() {
+            self.dsi.delay_ms(timing::RESET_DELAY_MS);
+        }
+        self.initialized = false;
+        self.tear_mode = None;
+        self.init_with(DisplayConfig::new(self.orientation, self.color_format))?;
+
+        for config in layers.into_iter().flatten() {
+            self.apply_layer(config)?;
+        }
+        self.set_brightness(brightness)?;
+        if let Some(mode) = tear_mode {
+            self.enable_tearing_effect(mode)?;
+        }
+        if let Some((start, end)) = partial_area {
+            self.set_partial_area(start, end)?;
+        }
+        match power_mode {
+            PowerMode::Normal => Ok(()),
+            PowerMode::Sleep => self.enter_sleep(),
+            mode => self.set_power_mode(mode),
+        }
+    }
+
+    fn reset_host(&mut self) -> Result<(), DriverError<D, L>> {
+        self.dsi.reset()
+            .map_err(|error| DisplayError::Dsi(DsiError::host(error)))
+    }
+
+    /// Get the reset pin, if the driver was given one
+    pub fn reset_pin(&self) -> Option<&R> {
+        self.reset_pin.as_ref()
+    }
+
+    fn pulse_reset_pin(&mut self) -> Result<(), DriverError<D, L>> {
+        if let Some(pin) = self.reset_pin.as_mut() {
+            pulse_reset(pin, &mut self.dsi, timing::RESET_PULSE_MS, timing::RESET_RELEASE_MS)
+                .map_err(|_| DisplayError::CommError)?;
+        }
+        Ok(())
+    }
+}
+
+impl<S, D, L, F, R> DisplayPanel for DsiPanelDriver<S, D, L, F, R>
+where
+    S: PanelSpec,
+    D: DsiInterface,
+    L: LtdcInterface,
+    F: FramebufferInterface,
+    F::Pixel: DcsPixel,
+    R: OutputPin,
+{
+    type Error = DriverError<D, L>;
+    type Pixel = F::Pixel;
+
+    fn init(&mut self, config: DisplayConfig) -> Result<(), DriverError<D, L>> {
+        DsiPanelDriver::init_with(self, config)
+    }
+
+    fn is_initialized(&self) -> bool {
+        DsiPanelDriver::is_initialized(self)
+    }
+
+    fn set_orientation(&mut self, orientation: Orientation) -> Result<(), DriverError<D, L>> {
+        DsiPanelDriver::set_orientation(self, orientation)
+    }
+
+    fn get_dimensions(&self) -> (u16, u16) {
+        DsiPanelDriver::get_dimensions(self)
+    }
+
+    fn fill_rect(&mut self, x: u16, y: u16, width: u16, height: u16, color: F::Pixel) -> Result<(), DriverError<D, L>> {
+        DsiPanelDriver::fill_rect(self, x, y, width, height, color)
+    }
+
+    fn set_pixel(&mut self, x: u16, y: u16, color: F::Pixel) -> Result<(), DriverError<D, L>> {
+        DsiPanelDriver::set_pixel(self, x, y, color)
+    }
+
+    fn clear(&mut self, color: F::Pixel) -> Result<(), DriverError<D, L>> {
+        DsiPanelDriver::clear(self, color)
+    }
+
+    fn flush(&mut self) -> Result<(), DriverError<D, L>> {
+        DsiPanelDriver::present(self)
+    }
+
+    fn power_mode(&self) -> PowerMode {
+        DsiPanelDriver::power_mode(self)
+    }
+
+    fn set_power_mode(&mut self, mode: PowerMode) -> Result<(), DriverError<D, L>> {
+        DsiPanelDriver::set_power_mode(self, mode)
+    }
+
+    fn enter_sleep(&mut self) -> Result<(), DriverError<D, L>> {
+        DsiPanelDriver::enter_sleep(self)
+    }
+
+    fn exit_sleep(&mut self) -> Result<(), DriverError<D, L>> {
+        DsiPanelDriver::exit_sleep(self)
+    }
+
+    fn set_brightness(&mut self, brightness: u8) -> Result<(), DriverError<D, L>> {
+        DsiPanelDriver::set_brightness(self, brightness)
+    }
+}
+
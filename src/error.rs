@@ -0,0 +1,207 @@
+//! Driver errors and bus error context
+//!
+//! DSI panel drivers keep the error returned by the `DsiInterface`
+//! implementation instead of flattening it, together with the DCS command
+//! that was being sent and the stage of `init` that was running. A bring-up
+//! that fails on hardware can then be traced to a single transfer.
+//!
+//! DBI panel drivers keep the `DisplayBus` error with the same context. The
+//! context is an `ErrorContext` apart from the bus error, so recording it is
+//! not generic over the bus.
+
+use crate::otm8009a::defs::PowerMode;
+use crate::traits::PanelId;
+
+/// Stage of a DSI panel bring-up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InitStage {
+    /// Reading RDID1..RDID3
+    ReadId,
+    /// Step `n` of the power-up script
+    PowerUp(u16),
+    /// Setting COLMOD
+    PixelFormat,
+    /// Setting MADCTL and the column and page address windows
+    Orientation,
+    /// Step `n` of the display-on script
+    DisplayOn(u16),
+}
+
+impl InitStage {
+    /// Short name of the stage, e.g. for an Erlang atom
+    pub const fn name(self) -> &'static str {
+        match self {
+            InitStage::ReadId => "read_id",
+            InitStage::PowerUp(_) => "power_up",
+            InitStage::PixelFormat => "pixel_format",
+            InitStage::Orientation => "orientation",
+            InitStage::DisplayOn(_) => "display_on",
+        }
+    }
+
+    /// Index of the failed step, for stages that play back a script
+    pub const fn step(self) -> Option<u16> {
+        match self {
+            InitStage::PowerUp(step) | InitStage::DisplayOn(step) => Some(step),
+            _ => None,
+        }
+    }
+}
+
+/// Where a transfer failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ErrorContext {
+    /// DCS command byte, `None` for host operations such as a reset or a
+    /// tearing effect wait
+    pub opcode: Option<u8>,
+    /// Stage of `init` that was running, `None` outside bring-up
+    pub stage: Option<InitStage>,
+}
+
+impl ErrorContext {
+    /// A DCS write or read of `opcode`
+    pub const fn command(opcode: u8) -> Self {
+        Self { opcode: Some(opcode), stage: None }
+    }
+
+    /// Record the init stage, unless a more specific one is already set
+    pub const fn during(mut self, stage: InitStage) -> Self {
+        if self.stage.is_none() {
+            self.stage = Some(stage);
+        }
+        self
+    }
+}
+
+impl core::fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        if let Some(opcode) = self.opcode {
+            write!(f, " on command 0x{:02X}", opcode)?;
+        }
+        match self.stage {
+            Some(stage) => match stage.step() {
+                Some(step) => write!(f, " during {} step {}", stage.name(), step),
+                None => write!(f, " during {}", stage.name()),
+            },
+            None => Ok(()),
+        }
+    }
+}
+
+/// A failed transfer on a DSI link or DBI bus
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BusError<E> {
+    /// Command and init stage of the failed transfer
+    pub context: ErrorContext,
+    /// Error returned by the `DsiInterface` or `DisplayBus`
+    pub error: E,
+}
+
+/// A failed DSI transfer
+pub type DsiError<E> = BusError<E>;
+
+impl<E> BusError<E> {
+    /// A DCS write or read of `opcode` failed
+    pub const fn command(opcode: u8, error: E) -> Self {
+        Self { context: ErrorContext::command(opcode), error }
+    }
+
+    /// A DSI host operation failed
+    pub const fn host(error: E) -> Self {
+        Self { context: ErrorContext { opcode: None, stage: None }, error }
+    }
+
+    /// Record the init stage, unless a more specific one is already set
+    pub fn during(mut self, stage: InitStage) -> Self {
+        self.context = self.context.during(stage);
+        self
+    }
+}
+
+impl<E: core::fmt::Debug> core::fmt::Display for BusError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "{:?}{}", self.error, self.context)
+    }
+}
+
+/// Errors returned by the DSI panel drivers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplayError<DE, LE> {
+    /// Display not ready
+    NotReady,
+    /// Invalid configuration
+    InvalidConfig,
+    /// The backlight, reset pin or platform failed
+    CommError,
+    /// Timeout waiting for display
+    Timeout,
+    /// Invalid coordinates
+    InvalidCoordinates,
+    /// Unsupported operation
+    Unsupported,
+    /// The panel ID does not match the controller the driver was built for
+    WrongPanelId(u8),
+    /// No driver matches the ID read from the panel
+    UnknownPanel(PanelId),
+    /// The power state machine has no step between these modes
+    IllegalTransition { from: PowerMode, to: PowerMode },
+    /// A DSI transfer failed
+    Dsi(DsiError<DE>),
+    /// The LTDC failed
+    Ltdc(LE),
+}
+
+impl<DE: core::fmt::Debug, LE: core::fmt::Debug> core::fmt::Display for DisplayError<DE, LE> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            DisplayError::NotReady => write!(f, "Display not ready"),
+            DisplayError::InvalidConfig => write!(f, "Invalid configuration"),
+            DisplayError::CommError => write!(f, "Communication error"),
+            DisplayError::Timeout => write!(f, "Timeout"),
+            DisplayError::InvalidCoordinates => write!(f, "Invalid coordinates"),
+            DisplayError::Unsupported => write!(f, "Unsupported operation"),
+            DisplayError::WrongPanelId(id) => write!(f, "Unexpected panel ID 0x{:02X}", id),
+            DisplayError::UnknownPanel(id) => write!(
+                f,
+                "Unknown panel with ID {:02X} {:02X} {:02X}",
+                id.manufacturer, id.version, id.driver
+            ),
+            DisplayError::IllegalTransition { from, to } => {
+                write!(f, "Illegal power transition from {:?} to {:?}", from, to)
+            }
+            DisplayError::Dsi(error) => write!(f, "DSI error {}", error),
+            DisplayError::Ltdc(error) => write!(f, "LTDC error {:?}", error),
+        }
+    }
+}
+
+/// Errors returned by the DBI panel drivers
+#[cfg(feature = "dbi")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DbiError<E> {
+    /// Display not ready
+    NotReady,
+    /// Invalid configuration
+    InvalidConfig,
+    /// Invalid coordinates
+    InvalidCoordinates,
+    /// The power state machine has no step between these modes
+    IllegalTransition { from: PowerMode, to: PowerMode },
+    /// A write to the `DisplayBus` failed
+    Bus(BusError<E>),
+}
+
+#[cfg(feature = "dbi")]
+impl<E: core::fmt::Debug> core::fmt::Display for DbiError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            DbiError::NotReady => write!(f, "Display not ready"),
+            DbiError::InvalidConfig => write!(f, "Invalid configuration"),
+            DbiError::InvalidCoordinates => write!(f, "Invalid coordinates"),
+            DbiError::IllegalTransition { from, to } => {
+                write!(f, "Illegal power transition from {:?} to {:?}", from, to)
+            }
+            DbiError::Bus(error) => write!(f, "DBI error {}", error),
+        }
+    }
+}
@@ -0,0 +1,160 @@
+//! Slice-backed framebuffer
+//!
+//! `SliceFramebuffer` draws into caller-supplied memory, typically a buffer
+//! in external SDRAM that the LTDC scans out. Lines may be padded: `stride`
+//! is the distance between the starts of two lines in pixels.
+
+use crate::traits::{pixel::{Rgb565, L8}, *};
+
+/// Framebuffer over a `&'static mut` pixel slice
+#[derive(Debug)]
+pub struct SliceFramebuffer<P: PixelType = Rgb565> {
+    buffer: &'static mut [P],
+    width: u16,
+    height: u16,
+    stride: usize,
+}
+
+impl<P: PixelType> SliceFramebuffer<P> {
+    /// Wrap `buffer` as a tightly packed `width` x `height` framebuffer
+    ///
+    /// Returns `None` if the buffer is too small.
+    pub fn new(buffer: &'static mut [P], width: u16, height: u16) -> Option<Self> {
+        Self::with_stride(buffer, width, height, width as usize)
+    }
+
+    /// Wrap `buffer` as a `width` x `height` framebuffer whose lines start
+    /// `stride` pixels apart
+    ///
+    /// Returns `None` if `stride` is shorter than a line or the buffer is too
+    /// small. Drawing relies on `stride * height` fitting in the buffer.
+    pub fn with_stride(buffer: &'static mut [P], width: u16, height: u16, stride: usize) -> Option<Self> {
+        let len = stride.checked_mul(height as usize)?;
+        if stride < width as usize || buffer.len() < len {
+            return None;
+        }
+
+        Some(Self { buffer, width, height, stride })
+    }
+
+    /// Get the distance between two lines in pixels
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Read back a single pixel
+    pub fn get_pixel(&self, x: u16, y: u16) -> Option<P> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        Some(self.buffer[self.index(x, y)])
+    }
+
+    /// Get one visible line, without its padding
+    pub fn row(&self, y: u16) -> Option<&[P]> {
+        if y >= self.height {
+            return None;
+        }
+        let start = self.index(0, y);
+        Some(&self.buffer[start..start + self.width as usize])
+    }
+
+    /// Get mutable access to one visible line, without its padding
+    pub fn row_mut(&mut self, y: u16) -> Option<&mut [P]> {
+        if y >= self.height {
+            return None;
+        }
+        let start = self.index(0, y);
+        Some(&mut self.buffer[start..start + self.width as usize])
+    }
+
+    /// Release the underlying memory
+    pub fn into_inner(self) -> &'static mut [P] {
+        self.buffer
+    }
+
+    fn index(&self, x: u16, y: u16) -> usize {
+        y as usize * self.stride + x as usize
+    }
+}
+
+impl SliceFramebuffer<Rgb565> {
+    /// Wrap a raw RGB565 buffer, e.g. one handed out by C code
+    pub fn from_u16(buffer: &'static mut [u16], width: u16, height: u16, stride: usize) -> Option<Self> {
+        // Rgb565 is a transparent wrapper around u16
+        let buffer = unsafe { core::slice::from_raw_parts_mut(buffer.as_mut_ptr() as *mut Rgb565, buffer.len()) };
+        Self::with_stride(buffer, width, height, stride)
+    }
+}
+
+impl SliceFramebuffer<L8> {
+    /// Wrap a raw buffer of CLUT indices
+    pub fn from_u8(buffer: &'static mut [u8], width: u16, height: u16, stride: usize) -> Option<Self> {
+        // L8 is a transparent wrapper around u8
+        let buffer = unsafe { core::slice::from_raw_parts_mut(buffer.as_mut_ptr() as *mut L8, buffer.len()) };
+        Self::with_stride(buffer, width, height, stride)
+    }
+}
+
+impl<P: PixelType> FramebufferInterface for SliceFramebuffer<P> {
+    type Pixel = P;
+
+    fn fill_rect(&mut self, x: u16, y: u16, width: u16, height: u16, color: P) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+        let end_x = core::cmp::min(x.saturating_add(width), self.width) as usize;
+        let end_y = core::cmp::min(y.saturating_add(height), self.height);
+
+        for row in y..end_y {
+            let start = self.index(0, row);
+            self.buffer[start + x as usize..start + end_x].fill(color);
+        }
+    }
+
+    fn set_pixel(&mut self, x: u16, y: u16, color: P) {
+        if x < self.width && y < self.height {
+            let index = self.index(x, y);
+            self.buffer[index] = color;
+        }
+    }
+
+    fn clear(&mut self, color: P) {
+        if self.stride == self.width as usize {
+            self.buffer[..self.stride * self.height as usize].fill(color);
+        } else {
+            self.fill_rect(0, 0, self.width, self.height, color);
+        }
+    }
+
+    fn get_dimensions(&self) -> (u16, u16) {
+        (self.width, self.height)
+    }
+
+    fn get_row(&self, y: u16) -> Option<&[P]> {
+        self.row(y)
+    }
+
+    fn write_row(&mut self, x: u16, y: u16, pixels: &[P]) {
+        if x >= self.width {
+            return;
+        }
+        if let Some(row) = self.row_mut(y) {
+            let len = core::cmp::min(pixels.len(), row.len() - x as usize);
+            row[x as usize..x as usize + len].copy_from_slice(&pixels[..len]);
+        }
+    }
+
+    fn get_buffer_ptr(&self) -> *const P {
+        self.buffer.as_ptr()
+    }
+
+    fn get_buffer_size(&self) -> usize {
+        // What the LTDC scans out, padding included
+        self.stride * self.height as usize * P::BYTES_PER_PIXEL
+    }
+
+    fn get_pitch(&self) -> usize {
+        self.stride * P::BYTES_PER_PIXEL
+    }
+}
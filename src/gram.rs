@@ -0,0 +1,99 @@
+//! Command-mode GRAM writes
+//!
+//! In DSI command mode the panel refreshes itself from its own frame memory
+//! (GRAM). `write_region` copies a dirty rectangle of a framebuffer into GRAM
+//! with CASET/PASET and RAMWR long writes, so the panel keeps its image
+//! without the LTDC streaming every frame in video mode.
+
+use crate::dcs::{ColorFormat, SET_COLUMN_ADDRESS, SET_PAGE_ADDRESS, WRITE_MEMORY_CONTINUE, WRITE_MEMORY_START};
+use crate::error::DsiError;
+use crate::script::write_payload;
+use crate::traits::*;
+
+/// Largest DCS long write sent at once, command byte included
+pub const MAX_LONG_WRITE: usize = 256;
+
+/// A failed GRAM write
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GramError<E> {
+    /// The panel's COLMOD expects pixels of another size than the
+    /// framebuffer holds
+    InvalidConfig,
+    /// A DSI transfer failed
+    Dsi(DsiError<E>),
+}
+
+/// Restrict memory writes to the inclusive window `(x0, y0)..=(x1, y1)`
+pub fn set_address_window<D: DsiInterface>(dsi: &mut D, x0: u16, y0: u16, x1: u16, y1: u16) -> Result<(), DsiError<D::Error>> {
+    let [x0h, x0l] = x0.to_be_bytes();
+    let [x1h, x1l] = x1.to_be_bytes();
+    let [y0h, y0l] = y0.to_be_bytes();
+    let [y1h, y1l] = y1.to_be_bytes();
+
+    write_payload(dsi, &[SET_COLUMN_ADDRESS, x0h, x0l, x1h, x1l])
+        .map_err(|error| DsiError::command(SET_COLUMN_ADDRESS, error))?;
+    write_payload(dsi, &[SET_PAGE_ADDRESS, y0h, y0l, y1h, y1l])
+        .map_err(|error| DsiError::command(SET_PAGE_ADDRESS, error))
+}
+
+/// Copy a `width` x `height` rectangle at `(x, y)` of `framebuffer` into GRAM
+///
+/// The rectangle is clipped to the framebuffer. Pixels are streamed row by row
+/// in RAMWR and then RAMWRC packets of at most `MAX_LONG_WRITE` bytes, never
+/// splitting a pixel across two packets. `color_format` is the COLMOD the
+/// panel was set to, and must take pixels of the framebuffer's size.
+pub fn write_region<D, F>(
+    dsi: &mut D,
+    framebuffer: &F,
+    color_format: ColorFormat,
+    x: u16,
+    y: u16,
+    width: u16,
+    height: u16,
+) -> Result<(), GramError<D::Error>>
+where
+    D: DsiInterface,
+    F: FramebufferInterface,
+    F::Pixel: DcsPixel,
+{
+    if color_format.dcs_bytes() != F::Pixel::DCS_BYTES {
+        return Err(GramError::InvalidConfig);
+    }
+
+    let (fb_width, fb_height) = framebuffer.get_dimensions();
+    let end_x = core::cmp::min(x.saturating_add(width), fb_width);
+    let end_y = core::cmp::min(y.saturating_add(height), fb_height);
+    if x >= end_x || y >= end_y {
+        return Ok(());
+    }
+
+    set_address_window(dsi, x, y, end_x - 1, end_y - 1)
+        .map_err(GramError::Dsi)?;
+
+    let mut packet = [0u8; MAX_LONG_WRITE];
+    packet[0] = WRITE_MEMORY_START;
+    let mut len = 1;
+
+    for row in y..end_y {
+        let Some(line) = framebuffer.get_row(row) else {
+            break;
+        };
+
+        for &pixel in &line[x as usize..end_x as usize] {
+            if len + F::Pixel::DCS_BYTES > MAX_LONG_WRITE {
+                write_payload(dsi, &packet[..len])
+                    .map_err(|error| GramError::Dsi(DsiError::command(packet[0], error)))?;
+                packet[0] = WRITE_MEMORY_CONTINUE;
+                len = 1;
+            }
+            pixel.write_dcs_bytes(&mut packet[len..]);
+            len += F::Pixel::DCS_BYTES;
+        }
+    }
+
+    if len > 1 {
+        write_payload(dsi, &packet[..len])
+            .map_err(|error| GramError::Dsi(DsiError::command(packet[0], error)))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,241 @@
+//! embedded-graphics integration
+//!
+//! Implements `DrawTarget<Color = Rgb565>` for the RGB565 `DisplayPanel`
+//! drivers and, through the `FramebufferTarget` wrapper, for any
+//! `FramebufferInterface`. Solid and contiguous fills are turned into
+//! `fill_rect` calls instead of per-pixel writes. Only compiled with the
+//! `embedded-graphics` feature.
+
+use core::convert::Infallible;
+
+use embedded_graphics_core::{
+    draw_target::DrawTarget,
+    geometry::{OriginDimensions, Point, Size},
+    pixelcolor::{IntoStorage, Rgb565},
+    primitives::{PointsIter, Rectangle},
+    Pixel,
+};
+
+#[cfg(feature = "dbi")]
+use crate::dbi_panel::{DbiPanelDriver, DbiPanelSpec};
+use crate::dsi_panel::{DsiPanelDriver, PanelSpec};
+use crate::traits::{pixel, *};
+
+/// `DrawTarget` adapter for any RGB565 `FramebufferInterface`
+#[derive(Debug)]
+pub struct FramebufferTarget<F: FramebufferInterface> {
+    framebuffer: F,
+}
+
+impl<F: FramebufferInterface> FramebufferTarget<F> {
+    pub fn new(framebuffer: F) -> Self {
+        Self { framebuffer }
+    }
+
+    /// Get the wrapped framebuffer
+    pub fn framebuffer(&self) -> &F {
+        &self.framebuffer
+    }
+
+    /// Get mutable access to the wrapped framebuffer
+    pub fn framebuffer_mut(&mut self) -> &mut F {
+        &mut self.framebuffer
+    }
+
+    /// Unwrap the framebuffer
+    pub fn into_inner(self) -> F {
+        self.framebuffer
+    }
+}
+
+impl<F: FramebufferInterface<Pixel = pixel::Rgb565>> OriginDimensions for FramebufferTarget<F> {
+    fn size(&self) -> Size {
+        let (width, height) = self.framebuffer.get_dimensions();
+        Size::new(width as u32, height as u32)
+    }
+}
+
+impl<F: FramebufferInterface<Pixel = pixel::Rgb565>> DrawTarget for FramebufferTarget<F> {
+    type Color = Rgb565;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let bounds = self.framebuffer.get_dimensions();
+        draw_pixels(pixels, bounds, |x, y, color| {
+            self.framebuffer.set_pixel(x, y, color);
+            Ok(())
+        })
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let bounds = self.framebuffer.get_dimensions();
+        fill_runs(area, colors, bounds, |x, y, width, height, color| {
+            self.framebuffer.fill_rect(x, y, width, height, color);
+            Ok(())
+        })
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        if let Some((x, y, width, height)) = clip(area, self.framebuffer.get_dimensions()) {
+            self.framebuffer.fill_rect(x, y, width, height, pixel::Rgb565(color.into_storage()));
+        }
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.framebuffer.clear(pixel::Rgb565(color.into_storage()));
+        Ok(())
+    }
+}
+
+/// `DrawTarget` for a panel driver, written against `DisplayPanel` alone
+///
+/// Coherence rules out `impl<P: DisplayPanel> DrawTarget for P`, so each
+/// driver type opts in with one invocation.
+macro_rules! impl_panel_draw_target {
+    ($driver:ident<$($param:ident),+>, $($bounds:tt)+) => {
+        impl<$($param),+> OriginDimensions for $driver<$($param),+>
+        where
+            Self: DisplayPanel<Pixel = pixel::Rgb565>,
+            $($bounds)+
+        {
+            fn size(&self) -> Size {
+                let (width, height) = DisplayPanel::get_dimensions(self);
+                Size::new(width as u32, height as u32)
+            }
+        }
+
+        impl<$($param),+> DrawTarget for $driver<$($param),+>
+        where
+            Self: DisplayPanel<Pixel = pixel::Rgb565>,
+            $($bounds)+
+        {
+            type Color = Rgb565;
+            type Error = <Self as DisplayPanel>::Error;
+
+            fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+            where
+                I: IntoIterator<Item = Pixel<Self::Color>>,
+            {
+                let bounds = DisplayPanel::get_dimensions(self);
+                draw_pixels(pixels, bounds, |x, y, color| DisplayPanel::set_pixel(self, x, y, color))
+            }
+
+            fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+            where
+                I: IntoIterator<Item = Self::Color>,
+            {
+                let bounds = DisplayPanel::get_dimensions(self);
+                fill_runs(area, colors, bounds, |x, y, width, height, color| {
+                    DisplayPanel::fill_rect(self, x, y, width, height, color)
+                })
+            }
+
+            fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+                match clip(area, DisplayPanel::get_dimensions(self)) {
+                    Some((x, y, width, height)) => {
+                        DisplayPanel::fill_rect(self, x, y, width, height, pixel::Rgb565(color.into_storage()))
+                    }
+                    None => Ok(()),
+                }
+            }
+
+            fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+                DisplayPanel::clear(self, pixel::Rgb565(color.into_storage()))
+            }
+        }
+    };
+}
+
+impl_panel_draw_target!(
+    DsiPanelDriver<S, D, L, F, R>,
+    S: PanelSpec, D: DsiInterface, L: LtdcInterface, F: FramebufferInterface, R: OutputPin
+);
+#[cfg(feature = "dbi")]
+impl_panel_draw_target!(DbiPanelDriver<S, B>, S: DbiPanelSpec, B: DisplayBus);
+
+/// Intersect `area` with a `width` x `height` surface
+///
+/// Returns `(x, y, width, height)` of the visible part, or `None` if nothing
+/// of `area` is on screen.
+fn clip(area: &Rectangle, (width, height): (u16, u16)) -> Option<(u16, u16, u16, u16)> {
+    let screen = Rectangle::new(Point::zero(), Size::new(width as u32, height as u32));
+    let visible = area.intersection(&screen);
+    if visible.size.width == 0 || visible.size.height == 0 {
+        return None;
+    }
+
+    Some((
+        visible.top_left.x as u16,
+        visible.top_left.y as u16,
+        visible.size.width as u16,
+        visible.size.height as u16,
+    ))
+}
+
+fn in_bounds(point: Point, (width, height): (u16, u16)) -> bool {
+    point.x >= 0 && point.y >= 0 && point.x < width as i32 && point.y < height as i32
+}
+
+/// Write every on-screen pixel, silently dropping the rest
+fn draw_pixels<I, E>(
+    pixels: I,
+    bounds: (u16, u16),
+    mut set_pixel: impl FnMut(u16, u16, pixel::Rgb565) -> Result<(), E>,
+) -> Result<(), E>
+where
+    I: IntoIterator<Item = Pixel<Rgb565>>,
+{
+    for Pixel(point, color) in pixels {
+        if in_bounds(point, bounds) {
+            set_pixel(point.x as u16, point.y as u16, pixel::Rgb565(color.into_storage()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Fill `area` from a row-major color stream, merging horizontal runs of the
+/// same color into single `fill_rect` calls
+fn fill_runs<I, E>(
+    area: &Rectangle,
+    colors: I,
+    bounds: (u16, u16),
+    mut fill_rect: impl FnMut(u16, u16, u16, u16, pixel::Rgb565) -> Result<(), E>,
+) -> Result<(), E>
+where
+    I: IntoIterator<Item = Rgb565>,
+{
+    // (x, y, length, color) of the run being accumulated
+    let mut run: Option<(u16, u16, u16, pixel::Rgb565)> = None;
+
+    for (point, color) in area.points().zip(colors) {
+        if !in_bounds(point, bounds) {
+            continue;
+        }
+
+        let (x, y, color) = (point.x as u16, point.y as u16, pixel::Rgb565(color.into_storage()));
+        match run.as_mut() {
+            Some((run_x, run_y, length, run_color))
+                if *run_y == y && *run_x + *length == x && *run_color == color =>
+            {
+                *length += 1;
+            }
+            _ => {
+                if let Some((run_x, run_y, length, run_color)) = run.replace((x, y, 1, color)) {
+                    fill_rect(run_x, run_y, length, 1, run_color)?;
+                }
+            }
+        }
+    }
+
+    if let Some((x, y, length, color)) = run {
+        fill_rect(x, y, length, 1, color)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,151 @@
+//! Constants and definitions for the ILI9341 display driver
+
+/// Display dimensions
+pub const LCD_WIDTH: u16 = 320;
+pub const LCD_HEIGHT: u16 = 240;
+
+/// Color format constants
+///
+/// The serial interface carries 16-bit and 18-bit pixels only.
+pub const ILI9341_FORMAT_RGB565: u32 = crate::dcs::FORMAT_RGB565;
+pub const ILI9341_FORMAT_RGB666: u32 = crate::dcs::FORMAT_RGB666;
+
+/// Orientation constants
+pub const ILI9341_ORIENTATION_PORTRAIT: u32 = 0;
+pub const ILI9341_ORIENTATION_LANDSCAPE: u32 = 1;
+pub const ILI9341_ORIENTATION_PORTRAIT_FLIPPED: u32 = 2;
+pub const ILI9341_ORIENTATION_LANDSCAPE_FLIPPED: u32 = 3;
+
+/// ILI9341 specific DCS commands
+pub mod commands {
+    /// Standard DCS commands
+    pub const SOFT_RESET: u8 = 0x01;
+    pub const SLEEP_OUT: u8 = 0x11;
+    pub const DISPLAY_ON: u8 = 0x29;
+    pub const DISPLAY_OFF: u8 = 0x28;
+    pub const SLEEP_IN: u8 = 0x10;
+    pub const NOP: u8 = 0x00;
+
+    /// Memory access control
+    pub const SET_MEMORY_ACCESS_CONTROL: u8 = 0x36;
+    pub const SET_PIXEL_FORMAT: u8 = 0x3A;
+    pub const SET_COLUMN_ADDRESS: u8 = 0x2A;
+    pub const SET_PAGE_ADDRESS: u8 = 0x2B;
+    pub const WRITE_MEMORY_START: u8 = 0x2C;
+
+    /// Brightness
+    pub const WRITE_DISPLAY_BRIGHTNESS: u8 = 0x51;
+    pub const WRITE_CTRL_DISPLAY: u8 = 0x53;
+}
+
+/// Command data sequences for initialization
+pub mod init_sequences {
+    /// Undocumented vendor register set by every reference driver
+    pub const CMD_EF: [u8; 4] = [0xEF, 0x03, 0x80, 0x02];
+    /// Power control B
+    pub const CMD_POWER_CTRL_B: [u8; 4] = [0xCF, 0x00, 0xC1, 0x30];
+    /// Power on sequence control
+    pub const CMD_POWER_ON_SEQ: [u8; 5] = [0xED, 0x64, 0x03, 0x12, 0x81];
+    /// Driver timing control A
+    pub const CMD_DRIVER_TIMING_A: [u8; 4] = [0xE8, 0x85, 0x00, 0x78];
+    /// Power control A
+    pub const CMD_POWER_CTRL_A: [u8; 6] = [0xCB, 0x39, 0x2C, 0x00, 0x34, 0x02];
+    /// Pump ratio control
+    pub const CMD_PUMP_RATIO: [u8; 2] = [0xF7, 0x20];
+    /// Driver timing control B
+    pub const CMD_DRIVER_TIMING_B: [u8; 3] = [0xEA, 0x00, 0x00];
+    /// GVDD 4.60V
+    pub const CMD_POWER_CTRL1: [u8; 2] = [0xC0, 0x23];
+    /// Step-up factor
+    pub const CMD_POWER_CTRL2: [u8; 2] = [0xC1, 0x10];
+    /// VCOMH 4.25V, VCOML -1.5V
+    pub const CMD_VCOM_CTRL1: [u8; 3] = [0xC5, 0x3E, 0x28];
+    /// VCOM offset
+    pub const CMD_VCOM_CTRL2: [u8; 2] = [0xC7, 0x86];
+    /// Vertical scrolling start address 0
+    pub const CMD_SCROLL_START: [u8; 2] = [0x37, 0x00];
+    /// Frame rate 79Hz in normal mode
+    pub const CMD_FRAME_RATE: [u8; 3] = [0xB1, 0x00, 0x18];
+    /// Display function control
+    pub const CMD_DISPLAY_FUNCTION: [u8; 4] = [0xB6, 0x08, 0x82, 0x27];
+    /// 3-gamma function off
+    pub const CMD_3GAMMA_OFF: [u8; 2] = [0xF2, 0x00];
+    /// Gamma curve 1
+    pub const CMD_GAMMA_SET: [u8; 2] = [0x26, 0x01];
+    /// Positive and negative gamma correction
+    pub const CMD_GAMMA_POSITIVE: [u8; 16] = [
+        0xE0, 0x0F, 0x31, 0x2B, 0x0C, 0x0E, 0x08, 0x4E, 0xF1, 0x37, 0x07, 0x10, 0x03, 0x0E, 0x09, 0x00,
+    ];
+    pub const CMD_GAMMA_NEGATIVE: [u8; 16] = [
+        0xE1, 0x00, 0x0E, 0x14, 0x03, 0x11, 0x07, 0x31, 0xC1, 0x48, 0x08, 0x0F, 0x0C, 0x31, 0x36, 0x0F,
+    ];
+
+    /// Color format commands
+    pub const CMD_RGB565: [u8; 2] = [0x3A, 0x55];
+    pub const CMD_RGB666: [u8; 2] = [0x3A, 0x66];
+
+    /// Orientation commands (BGR panel)
+    pub const CMD_PORTRAIT: [u8; 2] = [0x36, 0x48];
+    pub const CMD_LANDSCAPE: [u8; 2] = [0x36, 0x28];
+    pub const CMD_PORTRAIT_FLIPPED: [u8; 2] = [0x36, 0x88];
+    pub const CMD_LANDSCAPE_FLIPPED: [u8; 2] = [0x36, 0xE8];
+}
+
+/// Single byte commands
+pub mod single_commands {
+    pub const NOP: [u8; 1] = [0x00];
+    pub const SOFT_RESET: [u8; 1] = [0x01];
+    pub const SLEEP_OUT: [u8; 1] = [0x11];
+    pub const SLEEP_IN: [u8; 1] = [0x10];
+    pub const DISPLAY_ON: [u8; 1] = [0x29];
+    pub const DISPLAY_OFF: [u8; 1] = [0x28];
+}
+
+/// Init scripts played back by `ILI9341Driver::init`
+///
+/// Pixel format and orientation depend on the caller and are sent between
+/// `POWER_UP` and `DISPLAY_ON`.
+pub mod scripts {
+    use super::{init_sequences::*, single_commands, timing};
+    use crate::script::InitStep::{self, *};
+
+    /// Software reset, power settings, gamma and sleep out
+    pub const POWER_UP: &[InitStep] = &[
+        Write(&single_commands::SOFT_RESET),
+        Delay(timing::RESET_DELAY_MS),
+
+        Write(&CMD_EF),
+        Write(&CMD_POWER_CTRL_B),
+        Write(&CMD_POWER_ON_SEQ),
+        Write(&CMD_DRIVER_TIMING_A),
+        Write(&CMD_POWER_CTRL_A),
+        Write(&CMD_PUMP_RATIO),
+        Write(&CMD_DRIVER_TIMING_B),
+        Write(&CMD_POWER_CTRL1),
+        Write(&CMD_POWER_CTRL2),
+        Write(&CMD_VCOM_CTRL1),
+        Write(&CMD_VCOM_CTRL2),
+        Write(&CMD_SCROLL_START),
+        Write(&CMD_FRAME_RATE),
+        Write(&CMD_DISPLAY_FUNCTION),
+        Write(&CMD_3GAMMA_OFF),
+        Write(&CMD_GAMMA_SET),
+        Write(&CMD_GAMMA_POSITIVE),
+        Write(&CMD_GAMMA_NEGATIVE),
+
+        Write(&single_commands::SLEEP_OUT),
+        Delay(timing::SLEEP_OUT_DELAY_MS),
+    ];
+
+    /// Display on
+    pub const DISPLAY_ON: &[InitStep] = &[
+        Write(&single_commands::DISPLAY_ON),
+        Delay(timing::DISPLAY_ON_DELAY_MS),
+    ];
+}
+
+/// Timing parameters for display initialization
+pub use crate::dbi::timing;
+
+/// Errors returned by `ILI9341Driver`, keeping the bus error
+pub type Ili9341Error<E> = crate::error::DbiError<E>;
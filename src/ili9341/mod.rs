@@ -0,0 +1,11 @@
+//! ILI9341 Display Driver Module
+//! 
+//! This module provides support for the ILI9341 display controller found on
+//! 240x320 SPI modules used with ESP32-class AtomVM boards.
+
+pub mod defs;
+pub mod driver;
+
+// Re-export the main types and functions
+pub use driver::{ILI9341Driver, Ili9341Spec};
+pub use defs::*;
@@ -12,13 +12,58 @@ extern crate alloc;
 
 // Module declarations
 pub mod otm8009a;
+#[cfg(feature = "nt35510")]
+pub mod nt35510;
+#[cfg(feature = "ili9341")]
+pub mod ili9341;
+#[cfg(feature = "st7789")]
+pub mod st7789;
 pub mod common;
+pub mod error;
 pub mod traits;
+pub mod framebuffer;
+pub mod swapchain;
+pub mod palette;
+pub mod backlight;
+pub mod reset;
+pub mod script;
+pub mod dcs;
+#[cfg(feature = "dbi")]
+pub mod dbi;
+#[cfg(feature = "dbi")]
+pub mod dbi_panel;
+pub mod dsi_panel;
+pub mod gram;
+pub mod panel;
+
+#[cfg(feature = "nifs")]
+pub mod platform;
+
+#[cfg(feature = "embedded-graphics")]
+pub mod graphics;
 
 #[cfg(test)]
 pub mod testing;
 
 // Re-exports
 pub use otm8009a::*;
+#[cfg(feature = "nt35510")]
+pub use nt35510::{NT35510Driver, Nt35510Error};
+#[cfg(feature = "ili9341")]
+pub use ili9341::{ILI9341Driver, Ili9341Error};
+#[cfg(feature = "st7789")]
+pub use st7789::{ST7789Driver, St7789Error};
 pub use common::*;
-pub use traits::*;
\ No newline at end of file
+pub use error::{BusError, DisplayError, DsiError, ErrorContext, InitStage};
+#[cfg(feature = "dbi")]
+pub use error::DbiError;
+pub use traits::*;
+pub use framebuffer::SliceFramebuffer;
+pub use swapchain::SwapChain;
+pub use palette::Palette;
+pub use backlight::GpioBacklight;
+pub use reset::NoPin;
+#[cfg(feature = "dbi")]
+pub use dbi_panel::{DbiPanelDriver, DbiPanelSpec};
+pub use dsi_panel::{DsiPanelDriver, PanelSpec};
+pub use panel::{Panel, PanelDriver};
\ No newline at end of file
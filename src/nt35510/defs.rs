@@ -0,0 +1,235 @@
+//! Constants and definitions for the NT35510 display driver
+
+use crate::error::DisplayError;
+
+/// Display dimensions
+pub const LCD_WIDTH: u16 = 800;
+pub const LCD_HEIGHT: u16 = 480;
+
+/// Value of the RDID2 (0xDB) register on an NT35510
+pub const NT35510_ID: u8 = 0x80;
+
+/// Color format constants
+pub const NT35510_FORMAT_RGB565: u32 = 0x55;
+pub const NT35510_FORMAT_RGB888: u32 = 0x77;
+
+/// Orientation constants
+pub const NT35510_ORIENTATION_PORTRAIT: u32 = 0;
+pub const NT35510_ORIENTATION_LANDSCAPE: u32 = 1;
+pub const NT35510_ORIENTATION_PORTRAIT_FLIPPED: u32 = 2;
+pub const NT35510_ORIENTATION_LANDSCAPE_FLIPPED: u32 = 3;
+
+/// NT35510 specific DCS commands
+pub mod commands {
+    /// Standard DCS commands
+    pub const SLEEP_OUT: u8 = 0x11;
+    pub const DISPLAY_ON: u8 = 0x29;
+    pub const DISPLAY_OFF: u8 = 0x28;
+    pub const SLEEP_IN: u8 = 0x10;
+    pub const NOP: u8 = 0x00;
+    
+    /// Partial and idle modes
+    pub const PARTIAL_MODE_ON: u8 = 0x12;
+    pub const NORMAL_MODE_ON: u8 = 0x13;
+    pub const SET_PARTIAL_AREA: u8 = 0x30;
+    pub const IDLE_MODE_OFF: u8 = 0x38;
+    pub const IDLE_MODE_ON: u8 = 0x39;
+    
+    /// Status reads
+    pub const GET_POWER_MODE: u8 = 0x0A;
+    pub const GET_ADDRESS_MODE: u8 = 0x0B;
+    pub const GET_PIXEL_FORMAT: u8 = 0x0C;
+    pub const GET_DIAGNOSTIC_RESULT: u8 = 0x0F;
+    pub const READ_ID1: u8 = 0xDA;
+    pub const READ_ID2: u8 = 0xDB;
+    pub const READ_ID3: u8 = 0xDC;
+    
+    /// Memory access control
+    pub const SET_MEMORY_ACCESS_CONTROL: u8 = 0x36;
+    pub const SET_PIXEL_FORMAT: u8 = 0x3A;
+    pub const SET_COLUMN_ADDRESS: u8 = 0x2A;
+    pub const SET_PAGE_ADDRESS: u8 = 0x2B;
+    pub const WRITE_MEMORY_START: u8 = 0x2C;
+    pub const WRITE_MEMORY_CONTINUE: u8 = 0x3C;
+    
+    /// Tearing effect
+    pub const SET_TEAR_OFF: u8 = 0x34;
+    pub const SET_TEAR_ON: u8 = 0x35;
+    pub const SET_TEAR_SCANLINE: u8 = 0x44;
+    
+    /// Manufacturer specific commands
+    pub const SET_PAGE: u8 = 0xF0;
+    
+    /// CABC commands
+    pub const WRITE_DISPLAY_BRIGHTNESS: u8 = 0x51;
+    pub const WRITE_CTRL_DISPLAY: u8 = 0x53;
+    pub const WRITE_CABC: u8 = 0x55;
+    pub const WRITE_CABC_MIN_BRIGHTNESS: u8 = 0x5E;
+}
+
+/// Command data sequences for initialization
+pub mod init_sequences {
+    /// Select manufacturer command page 1 (power settings)
+    pub const CMD_PAGE1: [u8; 6] = [0xF0, 0x55, 0xAA, 0x52, 0x08, 0x01];
+    /// Select manufacturer command page 0 (display settings)
+    pub const CMD_PAGE0: [u8; 6] = [0xF0, 0x55, 0xAA, 0x52, 0x08, 0x00];
+    
+    /// AVDD 5.2V and boosting ratio
+    pub const CMD_AVDD: [u8; 4] = [0xB0, 0x03, 0x03, 0x03];
+    pub const CMD_AVDD_RATIO: [u8; 4] = [0xB6, 0x46, 0x46, 0x46];
+    /// AVEE -5.2V and boosting ratio
+    pub const CMD_AVEE: [u8; 4] = [0xB1, 0x03, 0x03, 0x03];
+    pub const CMD_AVEE_RATIO: [u8; 4] = [0xB7, 0x36, 0x36, 0x36];
+    /// VCL -2.5V and boosting ratio
+    pub const CMD_VCL: [u8; 4] = [0xB2, 0x00, 0x00, 0x02];
+    pub const CMD_VCL_RATIO: [u8; 4] = [0xB8, 0x26, 0x26, 0x26];
+    /// VGH output
+    pub const CMD_VGH_OUTPUT: [u8; 2] = [0xBF, 0x01];
+    /// VGH 15V and boosting ratio
+    pub const CMD_VGH: [u8; 4] = [0xB3, 0x09, 0x09, 0x09];
+    pub const CMD_VGH_RATIO: [u8; 4] = [0xB9, 0x36, 0x36, 0x36];
+    /// VGL_REG -10V and VGLX boosting ratio
+    pub const CMD_VGL: [u8; 4] = [0xB5, 0x08, 0x08, 0x08];
+    pub const CMD_VGLX_RATIO: [u8; 4] = [0xBA, 0x26, 0x26, 0x26];
+    /// VGMP/VGSP 4.5V/0V
+    pub const CMD_VGMP: [u8; 4] = [0xBC, 0x00, 0x80, 0x00];
+    /// VGMN/VGSN -4.5V/0V
+    pub const CMD_VGMN: [u8; 4] = [0xBD, 0x00, 0x80, 0x00];
+    /// VCOM offset
+    pub const CMD_VCOM: [u8; 3] = [0xBE, 0x00, 0x50];
+    
+    /// Display option control
+    pub const CMD_DISPLAY_CTRL: [u8; 3] = [0xB1, 0xFC, 0x00];
+    /// Source hold time
+    pub const CMD_SOURCE_HOLD: [u8; 2] = [0xB6, 0x05];
+    /// Gate EQ control
+    pub const CMD_GATE_EQ: [u8; 3] = [0xB7, 0x70, 0x70];
+    /// Source EQ control (mode 2)
+    pub const CMD_SOURCE_EQ: [u8; 5] = [0xB8, 0x01, 0x03, 0x03, 0x03];
+    /// Inversion mode (2-dot)
+    pub const CMD_INVERSION: [u8; 4] = [0xBC, 0x02, 0x00, 0x00];
+    /// Display timing control
+    pub const CMD_TIMING_CTRL: [u8; 4] = [0xCC, 0x03, 0x00, 0x00];
+    /// Frame rate
+    pub const CMD_FRAME_RATE: [u8; 6] = [0xBA, 0x01, 0x84, 0x07, 0x31, 0x00];
+    
+    /// Color format commands
+    pub const CMD_RGB565: [u8; 2] = [0x3A, 0x55];
+    pub const CMD_RGB888: [u8; 2] = [0x3A, 0x77];
+    
+    /// Orientation commands
+    pub const CMD_PORTRAIT: [u8; 2] = [0x36, 0x00];
+    pub const CMD_LANDSCAPE: [u8; 2] = [0x36, 0x60];
+    pub const CMD_PORTRAIT_FLIPPED: [u8; 2] = [0x36, 0xC0];
+    pub const CMD_LANDSCAPE_FLIPPED: [u8; 2] = [0x36, 0xA0];
+    
+    /// Column address set for different orientations
+    pub const CMD_CASET_LANDSCAPE: [u8; 5] = [0x2A, 0x00, 0x00, 0x03, 0x1F]; // 0-799
+    pub const CMD_CASET_PORTRAIT: [u8; 5] = [0x2A, 0x00, 0x00, 0x01, 0xDF];  // 0-479
+    
+    /// Page address set for different orientations  
+    pub const CMD_PASET_LANDSCAPE: [u8; 5] = [0x2B, 0x00, 0x00, 0x01, 0xDF]; // 0-479
+    pub const CMD_PASET_PORTRAIT: [u8; 5] = [0x2B, 0x00, 0x00, 0x03, 0x1F];  // 0-799
+    
+    /// CABC (Content Adaptive Backlight Control) commands
+    pub const CMD_DISPLAY_BRIGHTNESS: [u8; 2] = [0x51, 0x7F];
+    pub const CMD_BRIGHTNESS_CTRL: [u8; 2] = [0x53, 0x2C];
+    pub const CMD_CABC_CTRL: [u8; 2] = [0x55, 0x02];
+    pub const CMD_CABC_MIN_BRIGHTNESS: [u8; 2] = [0x5E, 0xFF];
+}
+
+/// Single byte commands
+pub mod single_commands {
+    pub const NOP: [u8; 1] = [0x00];
+    pub const SLEEP_OUT: [u8; 1] = [0x11];
+    pub const SLEEP_IN: [u8; 1] = [0x10];
+    pub const DISPLAY_ON: [u8; 1] = [0x29];
+    pub const DISPLAY_OFF: [u8; 1] = [0x28];
+    pub const WRITE_MEMORY_START: [u8; 1] = [0x2C];
+}
+
+/// Init scripts played back by `NT35510Driver::init`
+///
+/// Pixel format and orientation depend on the caller and are sent between
+/// `POWER_UP` and `DISPLAY_ON`.
+pub mod scripts {
+    use super::{init_sequences::*, single_commands, timing};
+    use crate::script::InitStep::{self, *};
+
+    /// Power and display settings and sleep out
+    ///
+    /// Mirrors `NT35510_Init` from the ST BSP for the STM32F769I-DISCO.
+    pub const POWER_UP: &[InitStep] = &[
+        // Page 1: power settings
+        Write(&CMD_PAGE1),
+        Write(&CMD_AVDD),
+        Write(&CMD_AVDD_RATIO),
+        Write(&CMD_AVEE),
+        Write(&CMD_AVEE_RATIO),
+        Write(&CMD_VCL),
+        Write(&CMD_VCL_RATIO),
+        Write(&CMD_VGH_OUTPUT),
+        Write(&CMD_VGH),
+        Write(&CMD_VGH_RATIO),
+        Write(&CMD_VGL),
+        Write(&CMD_VGLX_RATIO),
+        Write(&CMD_VGMP),
+        Write(&CMD_VGMN),
+        Write(&CMD_VCOM),
+
+        // Page 0: display settings
+        Write(&CMD_PAGE0),
+        Write(&CMD_DISPLAY_CTRL),
+        Write(&CMD_SOURCE_HOLD),
+        Write(&CMD_GATE_EQ),
+        Write(&CMD_SOURCE_EQ),
+        Write(&CMD_INVERSION),
+        Write(&CMD_TIMING_CTRL),
+        Write(&CMD_FRAME_RATE),
+
+        Write(&single_commands::SLEEP_OUT),
+        Delay(timing::SLEEP_OUT_DELAY_MS),
+    ];
+
+    /// CABC defaults, display on and GRAM write start
+    pub const DISPLAY_ON: &[InitStep] = &[
+        Write(&CMD_DISPLAY_BRIGHTNESS),
+        Write(&CMD_BRIGHTNESS_CTRL),
+        Write(&CMD_CABC_CTRL),
+        Write(&CMD_CABC_MIN_BRIGHTNESS),
+        Write(&single_commands::DISPLAY_ON),
+        Delay(timing::DISPLAY_ON_DELAY_MS),
+        Write(&single_commands::WRITE_MEMORY_START),
+    ];
+}
+
+/// Power modes, shared with the OTM8009A
+pub use crate::otm8009a::defs::PowerMode;
+
+/// Health check results, shared with the OTM8009A
+pub use crate::otm8009a::defs::{HealthEvent, PanelStatus};
+
+/// Typed configuration, shared with the OTM8009A
+///
+/// Both panels are 800x480 and use the same orientation values, but the
+/// NT35510 has no RGB666 mode and rejects `ColorFormat::Rgb666` at init.
+pub use crate::otm8009a::defs::{presets, ColorFormat, DisplayConfig, Orientation};
+
+/// Timing parameters for display initialization
+pub mod timing {
+    /// Delays in milliseconds
+    pub const RESET_DELAY_MS: u32 = 10;
+    /// XRES low pulse, at least 10 us
+    pub const RESET_PULSE_MS: u32 = 1;
+    /// Reset completes within 120 ms of XRES going high
+    pub const RESET_RELEASE_MS: u32 = 120;
+    pub const SLEEP_OUT_DELAY_MS: u32 = 120;
+    pub const DISPLAY_ON_DELAY_MS: u32 = 40;
+    pub const DISPLAY_OFF_DELAY_MS: u32 = 40;
+    /// SLPIN must be followed by 120 ms before SLPOUT
+    pub const SLEEP_IN_DELAY_MS: u32 = 120;
+    pub const CMD_DELAY_MS: u32 = 1;
+}
+
+/// Errors returned by `NT35510Driver`
+pub type Nt35510Error<DE, LE> = DisplayError<DE, LE>;
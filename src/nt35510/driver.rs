@@ -0,0 +1,44 @@
+//! NT35510 Driver Implementation
+//! 
+//! Hardware abstraction layer for the NT35510 display controller.
+//! This driver is hardware-agnostic and works with any DSI/LTDC implementation.
+
+use crate::dsi_panel::{DsiPanelDriver, PanelSpec};
+use crate::nt35510::defs::*;
+use crate::reset::NoPin;
+use crate::script::InitScript;
+
+pub use crate::dsi_panel::DriverError;
+
+/// NT35510 specifics for `DsiPanelDriver`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Nt35510Spec;
+
+impl PanelSpec for Nt35510Spec {
+    fn id_register(&self) -> u8 {
+        commands::READ_ID2
+    }
+
+    fn id(&self) -> u8 {
+        NT35510_ID
+    }
+
+    fn dimensions(&self) -> (u16, u16) {
+        (LCD_WIDTH, LCD_HEIGHT)
+    }
+
+    fn power_up(&self) -> &'static InitScript {
+        scripts::POWER_UP
+    }
+
+    fn display_on(&self) -> &'static InitScript {
+        scripts::DISPLAY_ON
+    }
+
+    /// The NT35510 has no RGB666 mode
+    fn supports(&self, format: ColorFormat) -> bool {
+        format != ColorFormat::Rgb666
+    }
+}
+
+pub type NT35510Driver<D, L, F, R = NoPin> = DsiPanelDriver<Nt35510Spec, D, L, F, R>;
@@ -0,0 +1,11 @@
+//! NT35510 Display Driver Module
+//! 
+//! This module provides support for the NT35510 display controller
+//! fitted to later revisions of the STM32F769I-DISCO board.
+
+pub mod defs;
+pub mod driver;
+
+// Re-export the main types and functions
+pub use driver::{Nt35510Spec, NT35510Driver};
+pub use defs::*;
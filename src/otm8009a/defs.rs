@@ -1,13 +1,18 @@
 //! Constants and definitions for the OTM8009A display driver
 
+use crate::error::DisplayError;
+
 /// Display dimensions
 pub const LCD_WIDTH: u16 = 800;
 pub const LCD_HEIGHT: u16 = 480;
 
+/// Value of the RDID1 (0xDA) register on an OTM8009A
+pub const OTM8009A_ID: u8 = 0x40;
+
 /// Color format constants
-pub const OTM8009A_FORMAT_RGB565: u32 = 0x55;
-pub const OTM8009A_FORMAT_RGB888: u32 = 0x77;
-pub const OTM8009A_FORMAT_RGB666: u32 = 0x66;
+pub const OTM8009A_FORMAT_RGB565: u32 = crate::dcs::FORMAT_RGB565;
+pub const OTM8009A_FORMAT_RGB888: u32 = crate::dcs::FORMAT_RGB888;
+pub const OTM8009A_FORMAT_RGB666: u32 = crate::dcs::FORMAT_RGB666;
 
 /// Orientation constants
 pub const OTM8009A_ORIENTATION_PORTRAIT: u32 = 0;
@@ -24,12 +29,34 @@ pub mod commands {
     pub const SLEEP_IN: u8 = 0x10;
     pub const NOP: u8 = 0x00;
     
+    /// Partial and idle modes
+    pub const PARTIAL_MODE_ON: u8 = 0x12;
+    pub const NORMAL_MODE_ON: u8 = 0x13;
+    pub const SET_PARTIAL_AREA: u8 = 0x30;
+    pub const IDLE_MODE_OFF: u8 = 0x38;
+    pub const IDLE_MODE_ON: u8 = 0x39;
+    
+    /// Status reads
+    pub const GET_POWER_MODE: u8 = 0x0A;
+    pub const GET_ADDRESS_MODE: u8 = 0x0B;
+    pub const GET_PIXEL_FORMAT: u8 = 0x0C;
+    pub const GET_DIAGNOSTIC_RESULT: u8 = 0x0F;
+    pub const READ_ID1: u8 = 0xDA;
+    pub const READ_ID2: u8 = 0xDB;
+    pub const READ_ID3: u8 = 0xDC;
+    
     /// Memory access control
     pub const SET_MEMORY_ACCESS_CONTROL: u8 = 0x36;
     pub const SET_PIXEL_FORMAT: u8 = 0x3A;
     pub const SET_COLUMN_ADDRESS: u8 = 0x2A;
     pub const SET_PAGE_ADDRESS: u8 = 0x2B;
     pub const WRITE_MEMORY_START: u8 = 0x2C;
+    pub const WRITE_MEMORY_CONTINUE: u8 = 0x3C;
+    
+    /// Tearing effect
+    pub const SET_TEAR_OFF: u8 = 0x34;
+    pub const SET_TEAR_ON: u8 = 0x35;
+    pub const SET_TEAR_SCANLINE: u8 = 0x44;
     
     /// Manufacturer specific commands
     pub const SET_EXTC: u8 = 0xFF;
@@ -43,6 +70,7 @@ pub mod commands {
     pub const SET_GAMMA_CTRL2: u8 = 0xE1;
     
     /// CABC commands
+    pub const WRITE_DISPLAY_BRIGHTNESS: u8 = 0x51;
     pub const WRITE_CTRL_DISPLAY: u8 = 0x53;
     pub const WRITE_CABC: u8 = 0x55;
     pub const WRITE_CABC_MIN_BRIGHTNESS: u8 = 0x5E;
@@ -54,24 +82,113 @@ pub mod init_sequences {
     pub const CMD_EXTC: [u8; 4] = [0xFF, 0x80, 0x09, 0x01];
     
     /// Enter ORISE Command 2
-    pub const CMD_ORISE_ENTER: [u8; 3] = [0x80, 0x09, 0x00];
+    pub const CMD_ORISE_ENTER: [u8; 3] = [0xFF, 0x80, 0x09];
     
-    /// GVDD/NGVDD settings  
-    pub const CMD_GVDD_NGVDD: [u8; 3] = [0xC5, 0x17, 0x40];
+    /// GVDD/NGVDD settings (0xD800)
+    pub const CMD_GVDD_NGVDD: [u8; 3] = [0xD8, 0x79, 0x79];
     
     /// Exit CMD2 mode
-    pub const CMD_EXIT_CMD2: [u8; 4] = [0xFF, 0x00, 0x00, 0x00];
+    pub const CMD_EXIT_CMD2: [u8; 4] = [0xFF, 0xFF, 0xFF, 0xFF];
     
-    /// Gamma correction positive
+    /// Gamma correction 2.2+ table
     pub const CMD_GAMMA_POSITIVE: [u8; 17] = [
-        0xE0, 0x00, 0x09, 0x0F, 0x0E, 0x07, 0x10, 0x0B, 0x0A, 0x04, 0x07, 0x0B, 0x08, 0x0F, 0x10, 0x0A, 0x01
+        0xE1, 0x00, 0x09, 0x0F, 0x0E, 0x07, 0x10, 0x0B, 0x0A, 0x04, 0x07, 0x0B, 0x08, 0x0F, 0x10, 0x0A, 0x01
     ];
     
-    /// Gamma correction negative
+    /// Gamma correction 2.2- table
     pub const CMD_GAMMA_NEGATIVE: [u8; 17] = [
-        0xE1, 0x00, 0x09, 0x0F, 0x0E, 0x07, 0x10, 0x0B, 0x0A, 0x04, 0x07, 0x0B, 0x08, 0x0F, 0x10, 0x0A, 0x01
+        0xE2, 0x00, 0x09, 0x0F, 0x0E, 0x07, 0x10, 0x0B, 0x0A, 0x04, 0x07, 0x0B, 0x08, 0x0F, 0x10, 0x0A, 0x01
     ];
     
+    /// SD_PCH_CTRL (0xC480): source output level during porch and non-display area to GND
+    pub const CMD_SD_PCH_CTRL: [u8; 2] = [0xC4, 0x30];
+    /// Undocumented (0xC48A)
+    pub const CMD_C48A: [u8; 2] = [0xC4, 0x40];
+    /// PWR_CTRL4 (0xC5B1): enable GVDD test mode
+    pub const CMD_PWR_CTRL4: [u8; 2] = [0xC5, 0xA9];
+    /// PWR_CTRL2 (0xC591): pump 4 VGH 13.0V, pump 5 VGL -9.0V
+    pub const CMD_PWR_CTRL2_PUMP45: [u8; 2] = [0xC5, 0x34];
+    /// P_DRV_M (0xC0B4): column inversion
+    pub const CMD_P_DRV_M: [u8; 2] = [0xC0, 0x50];
+    /// VCOMDC (0xD900): VCOM -1.2625V
+    pub const CMD_VCOMDC: [u8; 2] = [0xD9, 0x4E];
+    /// OSC_ADJ (0xC181): 65Hz oscillator for idle/normal mode
+    pub const CMD_OSC_ADJ: [u8; 2] = [0xC1, 0x66];
+    /// RGB_VIDEO_SET (0xC1A1): internal video mode
+    pub const CMD_VIDEO_MODE_INTERNAL: [u8; 2] = [0xC1, 0x08];
+    /// PWR_CTRL2 (0xC592): pump 4 and 5 x6
+    pub const CMD_PWR_CTRL2_PUMP45_X6: [u8; 2] = [0xC5, 0x01];
+    /// PWR_CTRL2 (0xC594): default value rewrite
+    pub const CMD_PWR_CTRL2_DEFAULT: [u8; 2] = [0xC5, 0x33];
+    /// PANSET (0xC0A3): panel display timing setting 3
+    pub const CMD_PANEL_TIMING3: [u8; 2] = [0xC0, 0x1B];
+    /// PWR_CTRL1 (0xC582)
+    pub const CMD_PWR_CTRL1: [u8; 2] = [0xC5, 0x83];
+    /// Source driver precharge (0xC481)
+    pub const CMD_SD_PRECHARGE: [u8; 2] = [0xC4, 0x83];
+    /// (0xC1A1)
+    pub const CMD_C1A1: [u8; 2] = [0xC1, 0x0E];
+    /// Panel type (0xB3A6)
+    pub const CMD_PANEL_TYPE: [u8; 3] = [0xB3, 0x00, 0x01];
+    
+    /// GOAVST (0xCE80)
+    pub const CMD_GOAVST: [u8; 7] = [0xCE, 0x85, 0x01, 0x00, 0x84, 0x01, 0x00];
+    /// GOACLKA1/A2 (0xCEA0)
+    pub const CMD_GOACLKA1: [u8; 15] = [
+        0xCE, 0x18, 0x04, 0x03, 0x39, 0x00, 0x00, 0x00, 0x18, 0x03, 0x03, 0x3A, 0x00, 0x00, 0x00
+    ];
+    /// GOACLKA3/A4 (0xCEB0)
+    pub const CMD_GOACLKA3: [u8; 15] = [
+        0xCE, 0x18, 0x02, 0x03, 0x3B, 0x00, 0x00, 0x00, 0x18, 0x01, 0x03, 0x3C, 0x00, 0x00, 0x00
+    ];
+    /// GOAECLK (0xCFC0)
+    pub const CMD_GOAECLK: [u8; 11] = [0xCF, 0x01, 0x01, 0x20, 0x20, 0x00, 0x00, 0x01, 0x02, 0x00, 0x00];
+    /// GOA other options (0xCFD0)
+    pub const CMD_GOA_OPTIONS: [u8; 2] = [0xCF, 0x00];
+    
+    /// PANCTRLSET1..8 (0xCB80..0xCBF0)
+    pub const CMD_PANCTRLSET1: [u8; 11] = [0xCB, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+    pub const CMD_PANCTRLSET2: [u8; 16] = [
+        0xCB, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00
+    ];
+    pub const CMD_PANCTRLSET3: [u8; 16] = [
+        0xCB, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00
+    ];
+    pub const CMD_PANCTRLSET4: [u8; 11] = [0xCB, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+    pub const CMD_PANCTRLSET5: [u8; 16] = [
+        0xCB, 0x00, 0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00
+    ];
+    pub const CMD_PANCTRLSET6: [u8; 16] = [
+        0xCB, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x00, 0x00, 0x00
+    ];
+    pub const CMD_PANCTRLSET7: [u8; 11] = [0xCB, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+    pub const CMD_PANCTRLSET8: [u8; 11] = [0xCB, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF];
+    
+    /// PANU2D1..3 (0xCC80..0xCCA0): GOA signal mapping, scan up to down
+    pub const CMD_PANU2D1: [u8; 11] = [0xCC, 0x00, 0x26, 0x09, 0x0B, 0x01, 0x25, 0x00, 0x00, 0x00, 0x00];
+    pub const CMD_PANU2D2: [u8; 16] = [
+        0xCC, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00
+    ];
+    pub const CMD_PANU2D3: [u8; 16] = [
+        0xCC, 0x00, 0x00, 0x00, 0x00, 0x00, 0x25, 0x0C, 0x0A, 0x02, 0x26, 0x00, 0x00, 0x00, 0x00, 0x00
+    ];
+    
+    /// PAND2U1..3 (0xCCB0..0xCCD0): GOA signal mapping, scan down to up
+    pub const CMD_PAND2U1: [u8; 11] = [0xCC, 0x00, 0x25, 0x0C, 0x0A, 0x02, 0x26, 0x00, 0x00, 0x00, 0x00];
+    pub const CMD_PAND2U2: [u8; 16] = [
+        0xCC, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00
+    ];
+    pub const CMD_PAND2U3: [u8; 16] = [
+        0xCC, 0x00, 0x00, 0x00, 0x00, 0x00, 0x26, 0x09, 0x0B, 0x01, 0x25, 0x00, 0x00, 0x00, 0x00, 0x00
+    ];
+    
+    /// PWR_CTRL1 (0xC581): pump 1 min and max DM
+    pub const CMD_PUMP1_DM: [u8; 2] = [0xC5, 0x66];
+    /// (0xF5B6)
+    pub const CMD_F5B6: [u8; 2] = [0xF5, 0x06];
+    /// CABC LEDPWM frequency (0xC6B1): 19.5kHz
+    pub const CMD_CABC_PWM_FREQ: [u8; 2] = [0xC6, 0x06];
+    
     /// Color format commands
     pub const CMD_RGB565: [u8; 2] = [0x3A, 0x55];
     pub const CMD_RGB888: [u8; 2] = [0x3A, 0x77];
@@ -92,9 +209,10 @@ pub mod init_sequences {
     pub const CMD_PASET_PORTRAIT: [u8; 5] = [0x2B, 0x00, 0x00, 0x03, 0x1F];  // 0-799
     
     /// CABC (Content Adaptive Backlight Control) commands
-    pub const CMD_BRIGHTNESS_CTRL: [u8; 2] = [0x53, 0x24];
-    pub const CMD_CABC_CTRL: [u8; 2] = [0x55, 0x00];
-    pub const CMD_CABC_MIN_BRIGHTNESS: [u8; 2] = [0x5E, 0x00];
+    pub const CMD_DISPLAY_BRIGHTNESS: [u8; 2] = [0x51, 0x7F];
+    pub const CMD_BRIGHTNESS_CTRL: [u8; 2] = [0x53, 0x2C];
+    pub const CMD_CABC_CTRL: [u8; 2] = [0x55, 0x02];
+    pub const CMD_CABC_MIN_BRIGHTNESS: [u8; 2] = [0x5E, 0xFF];
     
     /// Final commands
     pub const CMD_DISPLAY_ON: [u8; 1] = [0x29];
@@ -107,10 +225,104 @@ pub mod init_sequences {
 pub mod single_commands {
     pub const NOP: [u8; 1] = [0x00];
     pub const SLEEP_OUT: [u8; 1] = [0x11];
+    pub const SLEEP_IN: [u8; 1] = [0x10];
     pub const DISPLAY_ON: [u8; 1] = [0x29];
+    pub const DISPLAY_OFF: [u8; 1] = [0x28];
     pub const WRITE_MEMORY_START: [u8; 1] = [0x2C];
 }
 
+/// Init scripts played back by `OTM8009ADriver::init`
+///
+/// Pixel format and orientation depend on the caller and are sent between
+/// `POWER_UP` and `DISPLAY_ON`.
+pub mod scripts {
+    use super::{init_sequences::*, single_commands, timing};
+    use crate::script::InitStep::{self, *};
+
+    /// Vendor register programming, gamma and sleep out
+    ///
+    /// Mirrors `OTM8009A_Init` from the ST BSP for the STM32F769I-DISCO.
+    pub const POWER_UP: &[InitStep] = &[
+        // Enter command 2 and enable the ORISE address shift function
+        Shifted(0x00, &CMD_EXTC),
+        Shifted(0x80, &CMD_ORISE_ENTER),
+
+        // Source driver and power
+        Shifted(0x80, &CMD_SD_PCH_CTRL),
+        Delay(timing::VENDOR_DELAY_MS),
+        Shifted(0x8A, &CMD_C48A),
+        Delay(timing::VENDOR_DELAY_MS),
+        Shifted(0xB1, &CMD_PWR_CTRL4),
+        Shifted(0x91, &CMD_PWR_CTRL2_PUMP45),
+        Shifted(0xB4, &CMD_P_DRV_M),
+        Shifted(0x00, &CMD_VCOMDC),
+
+        // Oscillator and video mode
+        Shifted(0x81, &CMD_OSC_ADJ),
+        Shifted(0xA1, &CMD_VIDEO_MODE_INTERNAL),
+
+        // Charge pumps, GVDD/NGVDD and panel timing
+        Shifted(0x92, &CMD_PWR_CTRL2_PUMP45_X6),
+        Shifted(0x95, &CMD_PWR_CTRL2_PUMP45),
+        Shifted(0x00, &CMD_GVDD_NGVDD),
+        Shifted(0x94, &CMD_PWR_CTRL2_DEFAULT),
+        Shifted(0xA3, &CMD_PANEL_TIMING3),
+        Shifted(0x82, &CMD_PWR_CTRL1),
+        Shifted(0x81, &CMD_SD_PRECHARGE),
+        Shifted(0xA1, &CMD_C1A1),
+        Shifted(0xA6, &CMD_PANEL_TYPE),
+
+        // GOA timing
+        Shifted(0x80, &CMD_GOAVST),
+        Shifted(0xA0, &CMD_GOACLKA1),
+        Shifted(0xB0, &CMD_GOACLKA3),
+        Shifted(0xC0, &CMD_GOAECLK),
+        Shifted(0xD0, &CMD_GOA_OPTIONS),
+        Shifted(0x80, &CMD_PANCTRLSET1),
+        Shifted(0x90, &CMD_PANCTRLSET2),
+        Shifted(0xA0, &CMD_PANCTRLSET3),
+        Shifted(0xB0, &CMD_PANCTRLSET4),
+        Shifted(0xC0, &CMD_PANCTRLSET5),
+        Shifted(0xD0, &CMD_PANCTRLSET6),
+        Shifted(0xE0, &CMD_PANCTRLSET7),
+        Shifted(0xF0, &CMD_PANCTRLSET8),
+        Shifted(0x80, &CMD_PANU2D1),
+        Shifted(0x90, &CMD_PANU2D2),
+        Shifted(0xA0, &CMD_PANU2D3),
+        Shifted(0xB0, &CMD_PAND2U1),
+        Shifted(0xC0, &CMD_PAND2U2),
+        Shifted(0xD0, &CMD_PAND2U3),
+
+        // Pump 1 and CABC PWM frequency
+        Shifted(0x81, &CMD_PUMP1_DM),
+        Shifted(0xB6, &CMD_F5B6),
+        Shifted(0xB1, &CMD_CABC_PWM_FREQ),
+
+        // Back to standard DCS commands
+        Shifted(0x00, &CMD_EXIT_CMD2),
+        Write(&single_commands::NOP),
+
+        // Gamma correction tables
+        Shifted(0x00, &CMD_GAMMA_POSITIVE),
+        Shifted(0x00, &CMD_GAMMA_NEGATIVE),
+
+        Write(&single_commands::SLEEP_OUT),
+        Delay(timing::SLEEP_OUT_DELAY_MS),
+    ];
+
+    /// CABC defaults, display on and GRAM write start
+    pub const DISPLAY_ON: &[InitStep] = &[
+        Write(&CMD_DISPLAY_BRIGHTNESS),
+        Write(&CMD_BRIGHTNESS_CTRL),
+        Write(&CMD_CABC_CTRL),
+        Write(&CMD_CABC_MIN_BRIGHTNESS),
+        Write(&single_commands::DISPLAY_ON),
+        Delay(timing::DISPLAY_ON_DELAY_MS),
+        Write(&single_commands::NOP),
+        Write(&single_commands::WRITE_MEMORY_START),
+    ];
+}
+
 /// Display orientations
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Orientation {
@@ -120,64 +332,251 @@ pub enum Orientation {
     LandscapeFlipped = 3,
 }
 
-/// Color formats supported by OTM8009A
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum ColorFormat {
-    Rgb565 = 0x55,
-    Rgb666 = 0x66,
-    Rgb888 = 0x77,
+impl Orientation {
+    /// Parse one of the `OTM8009A_ORIENTATION_*` values
+    pub const fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            OTM8009A_ORIENTATION_PORTRAIT => Some(Orientation::Portrait),
+            OTM8009A_ORIENTATION_LANDSCAPE => Some(Orientation::Landscape),
+            OTM8009A_ORIENTATION_PORTRAIT_FLIPPED => Some(Orientation::PortraitFlipped),
+            OTM8009A_ORIENTATION_LANDSCAPE_FLIPPED => Some(Orientation::LandscapeFlipped),
+            _ => None,
+        }
+    }
+
+    pub const fn is_portrait(self) -> bool {
+        matches!(self, Orientation::Portrait | Orientation::PortraitFlipped)
+    }
+
+    /// Visible width and height in this orientation of a panel that is
+    /// `native` in landscape
+    pub const fn dimensions(self, native: (u16, u16)) -> (u16, u16) {
+        let (width, height) = native;
+        if self.is_portrait() {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
 }
 
+/// Color formats supported by OTM8009A
+pub use crate::dcs::ColorFormat;
+
 /// Power modes
+///
+/// The panel moves `Off` ⇄ `Sleep` ⇄ `DisplayOff` ⇄ `Normal`, and between
+/// `Normal`, `PartialDisplay` and `IdleMode` while the display is on.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PowerMode {
-    Normal,
+    /// Panel asleep and DSI/LTDC in platform low power
+    Off,
+    /// Sleep in, the panel is not refreshed
     Sleep,
+    /// Sleep out, but the panel shows nothing and the LTDC is stopped
+    DisplayOff,
+    /// All rows refreshed in full color
+    Normal,
+    /// Only the rows set with PTLAR are refreshed, the rest is black
     PartialDisplay,
+    /// Reduced to 8 colors
     IdleMode,
 }
 
+impl PowerMode {
+    /// Derive the mode from the power mode status byte (0x0A)
+    pub const fn from_status(status: u8) -> Self {
+        const IDLE_ON: u8 = 1 << 6;
+        const PARTIAL_ON: u8 = 1 << 5;
+        const SLEEP_OUT: u8 = 1 << 4;
+        const DISPLAY_ON: u8 = 1 << 2;
+
+        if status & SLEEP_OUT == 0 {
+            PowerMode::Sleep
+        } else if status & DISPLAY_ON == 0 {
+            PowerMode::DisplayOff
+        } else if status & IDLE_ON != 0 {
+            PowerMode::IdleMode
+        } else if status & PARTIAL_ON != 0 {
+            PowerMode::PartialDisplay
+        } else {
+            PowerMode::Normal
+        }
+    }
+
+    /// Whether the panel is out of sleep with the display on
+    pub const fn is_display_on(self) -> bool {
+        matches!(self, PowerMode::Normal | PowerMode::PartialDisplay | PowerMode::IdleMode)
+    }
+
+    /// Check whether the state machine has a direct step from `self` to `to`
+    pub const fn can_step_to(self, to: PowerMode) -> bool {
+        use PowerMode::*;
+        matches!(
+            (self, to),
+            (Off, Sleep)
+                | (Sleep, Off | DisplayOff)
+                | (DisplayOff, Sleep | Normal)
+                | (Normal, DisplayOff | PartialDisplay | IdleMode)
+                | (PartialDisplay, Normal | IdleMode)
+                | (IdleMode, Normal | PartialDisplay)
+        )
+    }
+}
+
+/// Power mode (0x0A) and self-diagnostic (0x0F) registers read back from
+/// the panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PanelStatus {
+    pub power_mode: u8,
+    pub diagnostic: u8,
+}
+
+impl PanelStatus {
+    /// Power mode the panel reports
+    pub const fn mode(self) -> PowerMode {
+        PowerMode::from_status(self.power_mode)
+    }
+
+    /// Whether the panel loaded its registers correctly (0x0F bit 7)
+    pub const fn registers_loaded(self) -> bool {
+        self.diagnostic & (1 << 7) != 0
+    }
+
+    /// Whether the panel passed its functionality check (0x0F bit 6)
+    pub const fn functional(self) -> bool {
+        self.diagnostic & (1 << 6) != 0
+    }
+
+    /// Whether the panel is showing the display with intact registers
+    pub const fn is_healthy(self) -> bool {
+        self.mode().is_display_on() && self.registers_loaded() && self.functional()
+    }
+}
+
+/// Reported by a health check that found the panel blank
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthEvent {
+    /// The panel left `expected` or failed its self-diagnostic, e.g. after an
+    /// ESD event; `status` is `None` if it did not answer
+    Lost { expected: PowerMode, status: Option<PanelStatus> },
+    /// Reset and init brought the panel back
+    Recovered(PanelStatus),
+}
+
 /// Timing parameters for display initialization
 pub mod timing {
     /// Delays in milliseconds
     pub const RESET_DELAY_MS: u32 = 10;
+    /// XRES low pulse, at least 10 us
+    pub const RESET_PULSE_MS: u32 = 1;
+    /// Reset completes within 120 ms of XRES going high
+    pub const RESET_RELEASE_MS: u32 = 120;
     pub const SLEEP_OUT_DELAY_MS: u32 = 120;
     pub const DISPLAY_ON_DELAY_MS: u32 = 40;
+    pub const DISPLAY_OFF_DELAY_MS: u32 = 40;
+    /// SLPIN must be followed by 120 ms before SLPOUT
+    pub const SLEEP_IN_DELAY_MS: u32 = 120;
     pub const POWER_ON_DELAY_MS: u32 = 50;
     pub const CMD_DELAY_MS: u32 = 1;
+    pub const VENDOR_DELAY_MS: u32 = 10;
 }
 
 /// Configuration presets
 pub mod presets {
     use super::*;
     
-    /// Standard configuration for 800x480 landscape
-    pub const STANDARD_LANDSCAPE: DisplayConfig = DisplayConfig {
-        width: LCD_WIDTH,
-        height: LCD_HEIGHT,
-        orientation: Orientation::Landscape,
-        color_format: ColorFormat::Rgb565,
-        power_mode: PowerMode::Normal,
-    };
-    
-    /// Standard configuration for 480x800 portrait
-    pub const STANDARD_PORTRAIT: DisplayConfig = DisplayConfig {
-        width: LCD_HEIGHT,
-        height: LCD_WIDTH,
-        orientation: Orientation::Portrait,
-        color_format: ColorFormat::Rgb565,
-        power_mode: PowerMode::Normal,
-    };
+    /// Standard configuration for the whole panel in landscape
+    pub const STANDARD_LANDSCAPE: DisplayConfig =
+        DisplayConfig::new(Orientation::Landscape, ColorFormat::Rgb565);
+    
+    /// Standard configuration for the whole panel in portrait
+    pub const STANDARD_PORTRAIT: DisplayConfig =
+        DisplayConfig::new(Orientation::Portrait, ColorFormat::Rgb565);
 }
 
 /// Display configuration structure
+///
+/// The fields are only reachable through the constructors, so a config
+/// always has dimensions that match its orientation and a power mode the
+/// panel can be brought up into. The native size of the panel is not known
+/// here; drivers check the config against their panel spec at init.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct DisplayConfig {
-    pub width: u16,
-    pub height: u16,
-    pub orientation: Orientation,
-    pub color_format: ColorFormat,
-    pub power_mode: PowerMode,
+    dimensions: Option<(u16, u16)>,
+    orientation: Orientation,
+    color_format: ColorFormat,
+    power_mode: PowerMode,
+}
+
+impl DisplayConfig {
+    /// Full panel in the given orientation, brought up in `Normal` mode
+    pub const fn new(orientation: Orientation, color_format: ColorFormat) -> Self {
+        Self {
+            dimensions: None,
+            orientation,
+            color_format,
+            power_mode: PowerMode::Normal,
+        }
+    }
+
+    /// Check caller supplied dimensions against the orientation
+    ///
+    /// Returns `None` for an empty size or one whose shape does not fit
+    /// `orientation`, e.g. 480x800 with `Landscape` is rejected. Whether the
+    /// size is the panel's is checked by the driver at init.
+    pub const fn with_dimensions(
+        width: u16,
+        height: u16,
+        orientation: Orientation,
+        color_format: ColorFormat,
+    ) -> Option<Self> {
+        let (w, h) = orientation.dimensions((width, height));
+        if width == 0 || height == 0 || w < h {
+            return None;
+        }
+        let mut config = Self::new(orientation, color_format);
+        config.dimensions = Some((width, height));
+        Some(config)
+    }
+
+    /// Power mode to leave the panel in once initialized
+    ///
+    /// `Off` needs the platform and is rejected with `None`.
+    pub const fn with_power_mode(mut self, power_mode: PowerMode) -> Option<Self> {
+        if matches!(power_mode, PowerMode::Off) {
+            return None;
+        }
+        self.power_mode = power_mode;
+        Some(self)
+    }
+
+    /// Size given to `with_dimensions`, `None` for the full panel
+    pub const fn dimensions(&self) -> Option<(u16, u16)> {
+        self.dimensions
+    }
+
+    /// Visible size on a panel that is `native` in landscape, or `None` if
+    /// the config asks for another size
+    pub const fn dimensions_on(&self, native: (u16, u16)) -> Option<(u16, u16)> {
+        let (width, height) = self.orientation.dimensions(native);
+        match self.dimensions {
+            Some((w, h)) if w != width || h != height => None,
+            _ => Some((width, height)),
+        }
+    }
+
+    pub const fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    pub const fn color_format(&self) -> ColorFormat {
+        self.color_format
+    }
+
+    pub const fn power_mode(&self) -> PowerMode {
+        self.power_mode
+    }
 }
 
 impl Default for DisplayConfig {
@@ -186,32 +585,5 @@ impl Default for DisplayConfig {
     }
 }
 
-/// Error types specific to OTM8009A
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum Otm8009aError {
-    /// Display not ready
-    NotReady,
-    /// Invalid configuration
-    InvalidConfig,
-    /// Communication error
-    CommError,
-    /// Timeout waiting for display
-    Timeout,
-    /// Invalid coordinates
-    InvalidCoordinates,
-    /// Unsupported operation
-    Unsupported,
-}
-
-impl core::fmt::Display for Otm8009aError {
-    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
-        match self {
-            Otm8009aError::NotReady => write!(f, "Display not ready"),
-            Otm8009aError::InvalidConfig => write!(f, "Invalid configuration"),
-            Otm8009aError::CommError => write!(f, "Communication error"),
-            Otm8009aError::Timeout => write!(f, "Timeout"),
-            Otm8009aError::InvalidCoordinates => write!(f, "Invalid coordinates"),
-            Otm8009aError::Unsupported => write!(f, "Unsupported operation"),
-        }
-    }
-}
\ No newline at end of file
+/// Errors returned by `OTM8009ADriver`
+pub type Otm8009aError<DE, LE> = DisplayError<DE, LE>;
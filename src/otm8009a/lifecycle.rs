@@ -0,0 +1,346 @@
+//! Type-state OTM8009A driver
+//!
+//! `Otm8009a` wraps `OTM8009ADriver` and tracks the panel's lifecycle in its
+//! type, so drawing on a panel that was never initialized, or waking one that
+//! is not asleep, is a compile error instead of `NotReady` or
+//! `IllegalTransition` at runtime:
+//!
+//! ```text
+//! Uninitialized --init--> Ready <--sleep/wake--> Asleep <--power_off/on--> Off
+//!       ^                   |
+//!       +--failed recovery--+
+//! ```
+//!
+//! Transitions consume the driver. When one fails the driver is handed back
+//! in its previous state inside a `TransitionError`, except when a health
+//! check recovery reset the panel but could not initialize it again, which
+//! hands it back `Uninitialized`. The dynamic
+//! `OTM8009ADriver` API, which the NIF layer uses, stays available through
+//! `into_driver`.
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use crate::otm8009a::defs::*;
+use crate::otm8009a::driver::{DriverError, OTM8009ADriver};
+use crate::reset::NoPin;
+use crate::traits::*;
+
+/// Created with `new`, not talked to the panel yet
+pub struct Uninitialized;
+
+/// Initialized and showing the framebuffer, in normal, partial or idle mode
+pub struct Ready;
+
+/// Initialized, but the panel is in sleep in and the LTDC is stopped
+pub struct Asleep;
+
+/// Asleep with DSI and LTDC in platform low power
+pub struct Off;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Lifecycle state of an `Otm8009a`
+pub trait State: sealed::Sealed {}
+
+impl sealed::Sealed for Uninitialized {}
+impl sealed::Sealed for Ready {}
+impl sealed::Sealed for Asleep {}
+impl sealed::Sealed for Off {}
+impl State for Uninitialized {}
+impl State for Ready {}
+impl State for Asleep {}
+impl State for Off {}
+
+/// Lifecycle driver, naming its error type
+pub trait Lifecycle {
+    type Error;
+}
+
+/// A failed transition, with the driver in the state it can continue from
+#[derive(Debug)]
+pub struct TransitionError<T: Lifecycle> {
+    pub driver: T,
+    pub error: T::Error,
+}
+
+/// Result of a transition from `From` into `To`
+pub type Transition<From, To> = Result<To, TransitionError<From>>;
+
+/// A failed health check on `P`, with the driver in the state it can
+/// continue from
+pub enum HealthCheckError<P: Lifecycle, Reset: Lifecycle<Error = P::Error>> {
+    /// The panel is still initialized, e.g. its status could not be read or
+    /// a setting could not be restored after recovery
+    Ready(TransitionError<P>),
+    /// Recovery reset the panel and could not initialize it again
+    Reset(TransitionError<Reset>),
+}
+
+impl<P, Reset> fmt::Debug for HealthCheckError<P, Reset>
+where
+    P: Lifecycle + fmt::Debug,
+    Reset: Lifecycle<Error = P::Error> + fmt::Debug,
+    P::Error: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HealthCheckError::Ready(failed) => f.debug_tuple("Ready").field(failed).finish(),
+            HealthCheckError::Reset(failed) => f.debug_tuple("Reset").field(failed).finish(),
+        }
+    }
+}
+
+/// Result of a health check on `P`, handing the driver back as `Reset` if
+/// the panel could not be initialized again
+pub type HealthCheck<P, Reset> = Result<(P, PanelStatus), HealthCheckError<P, Reset>>;
+
+pub struct Otm8009a<S, D, L, F, R = NoPin>
+where
+    S: State,
+    D: DsiInterface,
+    L: LtdcInterface,
+    F: FramebufferInterface,
+    R: OutputPin,
+{
+    driver: OTM8009ADriver<D, L, F, R>,
+    _state: PhantomData<S>,
+}
+
+impl<S, D, L, F, R> fmt::Debug for Otm8009a<S, D, L, F, R>
+where
+    S: State,
+    D: DsiInterface,
+    L: LtdcInterface,
+    F: FramebufferInterface,
+    R: OutputPin,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Otm8009a")
+            .field("state", &core::any::type_name::<S>())
+            .field("power_mode", &self.driver.power_mode())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S, D, L, F, R> Lifecycle for Otm8009a<S, D, L, F, R>
+where
+    S: State,
+    D: DsiInterface,
+    L: LtdcInterface,
+    F: FramebufferInterface,
+    R: OutputPin,
+{
+    type Error = DriverError<D, L>;
+}
+
+impl<D, L, F> Otm8009a<Uninitialized, D, L, F>
+where
+    D: DsiInterface,
+    L: LtdcInterface,
+    F: FramebufferInterface,
+{
+    pub fn new(dsi: D, ltdc: L, framebuffer: F) -> Self {
+        OTM8009ADriver::new(dsi, ltdc, framebuffer).into()
+    }
+}
+
+/// Start the lifecycle from a driver built with a backlight or reset pin
+impl<D, L, F, R> From<OTM8009ADriver<D, L, F, R>> for Otm8009a<Uninitialized, D, L, F, R>
+where
+    D: DsiInterface,
+    L: LtdcInterface,
+    F: FramebufferInterface,
+    R: OutputPin,
+{
+    fn from(driver: OTM8009ADriver<D, L, F, R>) -> Self {
+        Otm8009a { driver, _state: PhantomData }
+    }
+}
+
+impl<S, D, L, F, R> Otm8009a<S, D, L, F, R>
+where
+    S: State,
+    D: DsiInterface,
+    L: LtdcInterface,
+    F: FramebufferInterface,
+    R: OutputPin,
+{
+    /// Get the wrapped driver, e.g. for its dimensions or power mode
+    pub fn driver(&self) -> &OTM8009ADriver<D, L, F, R> {
+        &self.driver
+    }
+
+    /// Fall back to the dynamic API, which checks the state at runtime
+    pub fn into_driver(self) -> OTM8009ADriver<D, L, F, R> {
+        self.driver
+    }
+
+    /// Draw into the framebuffer, which is allowed in every state
+    pub fn framebuffer_mut(&mut self) -> &mut F {
+        self.driver.framebuffer_mut()
+    }
+
+    fn into_state<T: State>(self) -> Otm8009a<T, D, L, F, R> {
+        Otm8009a { driver: self.driver, _state: PhantomData }
+    }
+
+    fn step<T: State>(
+        mut self,
+        step: impl FnOnce(&mut OTM8009ADriver<D, L, F, R>) -> Result<(), DriverError<D, L>>,
+    ) -> Transition<Self, Otm8009a<T, D, L, F, R>> {
+        match step(&mut self.driver) {
+            Ok(()) => Ok(self.into_state()),
+            Err(error) => Err(TransitionError { driver: self, error }),
+        }
+    }
+}
+
+impl<D, L, F, R> Otm8009a<Uninitialized, D, L, F, R>
+where
+    D: DsiInterface,
+    L: LtdcInterface,
+    F: FramebufferInterface,
+    R: OutputPin,
+{
+    /// Bring the panel up with the display on
+    ///
+    /// A config that asks for `Sleep` or `DisplayOff` is rejected with
+    /// `InvalidConfig`; call `sleep` on the result instead.
+    pub fn init(self, config: DisplayConfig) -> Transition<Self, Otm8009a<Ready, D, L, F, R>> {
+        self.step(|driver| match config.power_mode() {
+            PowerMode::Sleep | PowerMode::DisplayOff => Err(Otm8009aError::InvalidConfig),
+            _ => driver.init_with(config),
+        })
+    }
+}
+
+impl<D, L, F, R> Otm8009a<Ready, D, L, F, R>
+where
+    D: DsiInterface,
+    L: LtdcInterface,
+    F: FramebufferInterface,
+    R: OutputPin,
+{
+    pub fn fill_rect(&mut self, x: u16, y: u16, width: u16, height: u16, color: F::Pixel) -> Result<(), DriverError<D, L>> {
+        self.driver.fill_rect(x, y, width, height, color)
+    }
+
+    pub fn set_pixel(&mut self, x: u16, y: u16, color: F::Pixel) -> Result<(), DriverError<D, L>> {
+        self.driver.set_pixel(x, y, color)
+    }
+
+    pub fn clear(&mut self, color: F::Pixel) -> Result<(), DriverError<D, L>> {
+        self.driver.clear(color)
+    }
+
+    pub fn set_orientation(&mut self, orientation: Orientation) -> Result<(), DriverError<D, L>> {
+        self.driver.set_orientation(orientation)
+    }
+
+    pub fn set_brightness(&mut self, brightness: u8) -> Result<(), DriverError<D, L>> {
+        self.driver.set_brightness(brightness)
+    }
+
+    pub fn fade_brightness(&mut self, brightness: u8, duration_ms: u32) -> Result<(), DriverError<D, L>> {
+        self.driver.fade_brightness(brightness, duration_ms)
+    }
+
+    pub fn enter_partial_mode(&mut self, start: u16, end: u16) -> Result<(), DriverError<D, L>> {
+        self.driver.enter_partial_mode(start, end)
+    }
+
+    pub fn enter_idle_mode(&mut self) -> Result<(), DriverError<D, L>> {
+        self.driver.enter_idle_mode()
+    }
+
+    pub fn enter_normal_mode(&mut self) -> Result<(), DriverError<D, L>> {
+        self.driver.enter_normal_mode()
+    }
+
+    pub fn configure_layer(&mut self, config: LayerConfig) -> Result<(), DriverError<D, L>> {
+        self.driver.configure_layer(config)
+    }
+
+    pub fn update(&mut self) -> Result<(), DriverError<D, L>> {
+        self.driver.update()
+    }
+
+    pub fn present(&mut self) -> Result<(), DriverError<D, L>>
+    where
+        F::Pixel: DcsPixel,
+    {
+        self.driver.present()
+    }
+
+    pub fn flip(&mut self) -> Result<(), DriverError<D, L>>
+    where
+        F: PageFlip,
+    {
+        self.driver.flip()
+    }
+
+    /// Recover the panel after e.g. an ESD event, see
+    /// [`OTM8009ADriver::check_health`]
+    ///
+    /// Returns the panel with the status read last. If recovery reset the
+    /// panel but could not initialize it again, it is handed back
+    /// `Uninitialized`; any other failure hands it back `Ready`.
+    pub fn check_health(
+        mut self,
+        report: impl FnMut(HealthEvent),
+    ) -> HealthCheck<Self, Otm8009a<Uninitialized, D, L, F, R>> {
+        match self.driver.check_health(report) {
+            Ok(status) => Ok((self, status)),
+            // Recovery leaves the driver uninitialized until init succeeds
+            Err(error) if self.driver.is_initialized() => {
+                Err(HealthCheckError::Ready(TransitionError { driver: self, error }))
+            }
+            Err(error) => Err(HealthCheckError::Reset(TransitionError { driver: self.into_state(), error })),
+        }
+    }
+
+    /// Blank the panel and put it to sleep
+    pub fn sleep(self) -> Transition<Self, Otm8009a<Asleep, D, L, F, R>> {
+        self.step(OTM8009ADriver::enter_sleep)
+    }
+}
+
+impl<D, L, F, R> Otm8009a<Asleep, D, L, F, R>
+where
+    D: DsiInterface,
+    L: LtdcInterface,
+    F: FramebufferInterface,
+    R: OutputPin,
+{
+    /// Wake the panel and show the framebuffer again in normal mode
+    pub fn wake(self) -> Transition<Self, Otm8009a<Ready, D, L, F, R>> {
+        self.step(OTM8009ADriver::exit_sleep)
+    }
+
+    /// Let the platform cut DSI and LTDC clocks
+    pub fn power_off<P: PlatformInterface>(
+        self,
+        platform: &mut P,
+    ) -> Transition<Self, Otm8009a<Off, D, L, F, R>> {
+        self.step(|driver| driver.power_off(platform))
+    }
+}
+
+impl<D, L, F, R> Otm8009a<Off, D, L, F, R>
+where
+    D: DsiInterface,
+    L: LtdcInterface,
+    F: FramebufferInterface,
+    R: OutputPin,
+{
+    /// Bring the platform out of low power, leaving the panel asleep
+    pub fn power_on<P: PlatformInterface>(
+        self,
+        platform: &mut P,
+    ) -> Transition<Self, Otm8009a<Asleep, D, L, F, R>> {
+        self.step(|driver| driver.power_on(platform))
+    }
+}
@@ -5,11 +5,19 @@
 
 pub mod defs;
 pub mod driver;
+#[cfg(feature = "lifecycle")]
+// A failed transition hands the driver back, so its error is as large as
+// the driver
+#[allow(clippy::result_large_err)]
+pub mod lifecycle;
 pub mod nifs;
 
 // Re-export the main types and functions
-pub use driver::OTM8009ADriver;
+pub use driver::{Otm8009aSpec, OTM8009ADriver};
+#[cfg(feature = "lifecycle")]
+pub use lifecycle::{HealthCheckError, Otm8009a, TransitionError};
 pub use defs::*;
 
 #[cfg(feature = "nifs")]
+#[allow(unused_imports)]
 pub use nifs::*;
\ No newline at end of file
@@ -1,30 +1,65 @@
 //! OTM8009A NIF Functions
-//! 
+//!
 //! NIF implementations for AtomVM integration.
 //! This entire module is only compiled when the nifs feature is enabled.
 
 #[cfg(feature = "nifs")]
 mod nif_impl {
+    use core::ffi::c_void;
+
     use avmnif_rs::{
+        atom::{AtomTable, AtomTableOps},
+        create_resource,
         nif_collection,
-        term::{Context, Term, TermValue, NifResult, NifError},
+        resource::{enif_get_resource, enif_make_resource, enif_release_resource, ErlNifEnv, ERL_NIF_TERM},
+        resource_type,
+        term::{Context, Heap, Term, TermValue, NifResult, NifError},
     };
-    use crate::otm8009a::defs::{LCD_WIDTH, LCD_HEIGHT};
+    use crate::otm8009a::defs::*;
+    use crate::error::{DsiError, ErrorContext};
+    use crate::error::DisplayError;
+    use crate::panel::{Panel, PanelDriver, PanelError};
+    use crate::platform::{take_framebuffer, PlatformDsi, PlatformError, PlatformFramebuffer, PlatformLtdc};
+    use crate::traits::{pixel::Rgb565, DisplayPanel};
+
+    /// Driver for the detected panel, owned by an Erlang resource
+    type NifDriver = PanelDriver<PlatformDsi, PlatformLtdc, PlatformFramebuffer>;
+
+    /// Error of `NifDriver`, holding the platform hook status codes
+    type NifDisplayError = PanelError<PlatformDsi, PlatformLtdc>;
+
+    extern "C" {
+        // Provided by c/avmgl_terms.c
+        fn avmgl_make_tuple(ctx: *mut Context, elements: *mut usize, count: usize) -> usize;
+    }
+
+    unsafe extern "C" fn otm8009a_driver_dtor(_env: *mut ErlNifEnv, obj: *mut c_void) {
+        core::ptr::drop_in_place(obj as *mut NifDriver);
+    }
+
+    resource_type!(OTM8009A_DRIVER, NifDriver, otm8009a_driver_dtor);
 
     // Register the NIF collection
     nif_collection!(
         otm8009a,
-        init = otm8009a_nif_init,
+        init = otm8009a_init_resources,
         nifs = [
             ("init", 1, otm8009a_init),
             ("set_pixel", 4, otm8009a_set_pixel),
             ("fill_rect", 6, otm8009a_fill_rect),
             ("clear", 1, otm8009a_clear),
             ("get_info", 1, otm8009a_get_info),
+            ("panel", 1, otm8009a_panel),
             ("update", 1, otm8009a_update),
+            ("flush", 5, otm8009a_flush),
+            ("present", 1, otm8009a_present),
         ]
     );
 
+    fn otm8009a_init_resources(ctx: &mut avmnif_rs::Context) {
+        init_otm8009a_driver(ctx as *mut avmnif_rs::Context as *mut ErlNifEnv);
+    }
+
     // Initialize the display
     fn otm8009a_init(ctx: &mut Context, args: &[usize]) -> NifResult<usize> {
         if args.len() != 1 {
@@ -45,21 +80,34 @@ mod nif_impl {
         let height = config_tuple[1].as_int().ok_or(NifError::BadArg)?;
         let orientation = config_tuple[2].as_int().ok_or(NifError::BadArg)?;
 
-        // Validate parameters
-        if width <= 0 || height <= 0 || width > 1024 || height > 1024 {
-            return Err(NifError::BadArg);
+        // Validate parameters, the size must match the orientation
+        let orientation = u32::try_from(orientation).ok()
+            .and_then(Orientation::from_u32)
+            .ok_or(NifError::BadArg)?;
+        let width = u16::try_from(width).map_err(|_| NifError::BadArg)?;
+        let height = u16::try_from(height).map_err(|_| NifError::BadArg)?;
+        let config = DisplayConfig::with_dimensions(width, height, orientation, ColorFormat::Rgb565)
+            .ok_or(NifError::BadArg)?;
+
+        let framebuffer = take_framebuffer(width, height)
+            .ok_or(NifError::OutOfMemory)?;
+        let mut driver = match PanelDriver::probe(PlatformDsi, PlatformLtdc, framebuffer) {
+            Ok(driver) => driver,
+            Err(err) => return error_reply(ctx, err),
+        };
+        if let Err(err) = DisplayPanel::init(&mut driver, config) {
+            return error_reply(ctx, err);
         }
 
-        if orientation < 0 || orientation > 3 {
-            return Err(NifError::BadArg);
-        }
+        let resource: *mut c_void = create_resource!(OTM8009A_DRIVER, driver)?;
+        let env = ctx as *mut Context as *mut ErlNifEnv;
+        let handle = unsafe { enif_make_resource(env, resource) };
+
+        // The handle term now owns the resource
+        unsafe { enif_release_resource(resource) };
 
-        // TODO: Get driver from context and initialize
-        // let mut driver = get_driver_from_context(ctx);
-        // driver.init(RGB565, orientation as u32)?;
-        
-        // TODO: Use proper atom creation API from avmnif-rs
-        Ok(Term::from_raw(0).raw())  // Placeholder
+        let ok = make_atom("ok")?;
+        make_tuple(ctx, &mut [ok, handle as usize])
     }
 
     // Set a single pixel
@@ -72,22 +120,22 @@ mod nif_impl {
         let x_term = Term::from_raw(args[0]);
         let y_term = Term::from_raw(args[1]);
         let color_term = Term::from_raw(args[2]);
-        let _handle_term = Term::from_raw(args[3]);
+        let handle_term = Term::from_raw(args[3]);
 
         let x = x_term.to_value()?.as_int().ok_or(NifError::BadArg)?;
         let y = y_term.to_value()?.as_int().ok_or(NifError::BadArg)?;
-        let _color = color_term.to_value()?.as_int().ok_or(NifError::BadArg)?;
+        let color = extract_rgb_color(color_term)?;
+
+        let panel = get_panel(ctx, handle_term)?;
+        let (width, height) = panel.get_dimensions();
 
         // Validate coordinates
-        if x < 0 || y < 0 || x >= LCD_WIDTH as i32 || y >= LCD_HEIGHT as i32 {
+        if x < 0 || y < 0 || x >= width as i32 || y >= height as i32 {
             return Err(NifError::BadArg);
         }
 
-        // TODO: Get driver from context and call set_pixel
-        // let mut driver = get_driver_from_context(ctx);
-        // driver.set_pixel(x as u16, y as u16, color as u16)?;
-
-        Ok(Term::from_raw(0).raw())  // Placeholder
+        let result = panel.set_pixel(x as u16, y as u16, Rgb565(color));
+        reply(ctx, result)
     }
 
     // Fill a rectangle
@@ -102,108 +150,273 @@ mod nif_impl {
         let width_term = Term::from_raw(args[2]);
         let height_term = Term::from_raw(args[3]);
         let color_term = Term::from_raw(args[4]);
-        let _handle_term = Term::from_raw(args[5]);
+        let handle_term = Term::from_raw(args[5]);
 
         let x = x_term.to_value()?.as_int().ok_or(NifError::BadArg)?;
         let y = y_term.to_value()?.as_int().ok_or(NifError::BadArg)?;
         let width = width_term.to_value()?.as_int().ok_or(NifError::BadArg)?;
         let height = height_term.to_value()?.as_int().ok_or(NifError::BadArg)?;
-        let _color = color_term.to_value()?.as_int().ok_or(NifError::BadArg)?;
+        let color = extract_rgb_color(color_term)?;
 
         // Validate parameters
         if x < 0 || y < 0 || width <= 0 || height <= 0 {
             return Err(NifError::BadArg);
         }
-        
-        if x + width > LCD_WIDTH as i32 || y + height > LCD_HEIGHT as i32 {
+
+        let panel = get_panel(ctx, handle_term)?;
+        let (display_width, display_height) = panel.get_dimensions();
+
+        if x + width > display_width as i32 || y + height > display_height as i32 {
             return Err(NifError::BadArg);
         }
 
-        // TODO: Get driver from context and call fill_rect
-        // let mut driver = get_driver_from_context(ctx);
-        // driver.fill_rect(x as u16, y as u16, width as u16, height as u16, color as u16)?;
-
-        Ok(Term::from_raw(0).raw())  // Placeholder
+        let result = panel.fill_rect(x as u16, y as u16, width as u16, height as u16, Rgb565(color));
+        reply(ctx, result)
     }
 
     // Clear the display
-    fn otm8009a_clear(_ctx: &mut Context, args: &[usize]) -> NifResult<usize> {
+    fn otm8009a_clear(ctx: &mut Context, args: &[usize]) -> NifResult<usize> {
         if args.len() != 1 {
             return Err(NifError::BadArity);
         }
 
-        let _handle_term = Term::from_raw(args[0]);
-
-        // TODO: Get driver from context and call clear
-        // let mut driver = get_driver_from_context(ctx);
-        // driver.clear(0x0000)?; // Clear to black
-
-        Ok(Term::from_raw(0).raw())  // Placeholder
+        let panel = get_panel(ctx, Term::from_raw(args[0]))?;
+        let result = panel.clear(Rgb565(0x0000)); // Clear to black
+        reply(ctx, result)
     }
 
     // Get display information
-    fn otm8009a_get_info(_ctx: &mut Context, args: &[usize]) -> NifResult<usize> {
+    fn otm8009a_get_info(ctx: &mut Context, args: &[usize]) -> NifResult<usize> {
         if args.len() != 1 {
             return Err(NifError::BadArity);
         }
 
-        let _handle_term = Term::from_raw(args[0]);
+        let panel = get_panel(ctx, Term::from_raw(args[0]))?;
+        let (width, height) = panel.get_dimensions();
+
+        // {ok, {Width, Height, rgb565}}
+        let info = make_tuple(ctx, &mut [
+            make_int(width as i32)?,
+            make_int(height as i32)?,
+            make_atom("rgb565")?,
+        ])?;
+        let ok = make_atom("ok")?;
+        make_tuple(ctx, &mut [ok, info])
+    }
+
+    // Get the detected panel controller
+    fn otm8009a_panel(ctx: &mut Context, args: &[usize]) -> NifResult<usize> {
+        if args.len() != 1 {
+            return Err(NifError::BadArity);
+        }
 
-        // TODO: Get driver from context and return dimensions
-        // let driver = get_driver_from_context(ctx);
-        // let (width, height) = driver.get_dimensions();
-        // Create tuple {ok, {width, height, pixel_format}}
-        
-        Ok(Term::from_raw(0).raw())  // Placeholder
+        let driver = get_driver(ctx, Term::from_raw(args[0]))?;
+        match driver.panel() {
+            Panel::Otm8009a => make_atom("otm8009a"),
+            #[cfg(feature = "nt35510")]
+            Panel::Nt35510 => make_atom("nt35510"),
+        }
     }
 
     // Update/refresh the display
-    fn otm8009a_update(_ctx: &mut Context, args: &[usize]) -> NifResult<usize> {
+    fn otm8009a_update(ctx: &mut Context, args: &[usize]) -> NifResult<usize> {
         if args.len() != 1 {
             return Err(NifError::BadArity);
         }
 
-        let _handle_term = Term::from_raw(args[0]);
+        let driver = get_driver(ctx, Term::from_raw(args[0]))?;
+        let result = driver.update();
+        reply(ctx, result)
+    }
+
+    // Push a dirty rectangle into panel GRAM for command mode
+    fn otm8009a_flush(ctx: &mut Context, args: &[usize]) -> NifResult<usize> {
+        if args.len() != 5 {
+            return Err(NifError::BadArity);
+        }
 
-        // TODO: Trigger display update
-        // This might involve LTDC refresh or similar
+        // Extract arguments: X, Y, Width, Height, Handle
+        let x = Term::from_raw(args[0]).to_value()?.as_int().ok_or(NifError::BadArg)?;
+        let y = Term::from_raw(args[1]).to_value()?.as_int().ok_or(NifError::BadArg)?;
+        let width = Term::from_raw(args[2]).to_value()?.as_int().ok_or(NifError::BadArg)?;
+        let height = Term::from_raw(args[3]).to_value()?.as_int().ok_or(NifError::BadArg)?;
 
-        Ok(Term::from_raw(0).raw())  // Placeholder
+        if x < 0 || y < 0 || width <= 0 || height <= 0 {
+            return Err(NifError::BadArg);
+        }
+
+        let driver = get_driver(ctx, Term::from_raw(args[4]))?;
+        let (display_width, display_height) = driver.get_dimensions();
+
+        if x + width > display_width as i32 || y + height > display_height as i32 {
+            return Err(NifError::BadArg);
+        }
+
+        let result = driver.flush_region(x as u16, y as u16, width as u16, height as u16);
+        reply(ctx, result)
+    }
+
+    // Show the framebuffer at the next vertical blanking / tearing effect
+    fn otm8009a_present(ctx: &mut Context, args: &[usize]) -> NifResult<usize> {
+        if args.len() != 1 {
+            return Err(NifError::BadArity);
+        }
+
+        let panel = get_panel(ctx, Term::from_raw(args[0]))?;
+        let result = panel.flush();
+        reply(ctx, result)
+    }
+
+    // Look up the driver behind a handle returned by init/1
+    fn get_driver<'a>(ctx: &mut Context, handle: Term) -> NifResult<&'a mut NifDriver> {
+        let env = ctx as *mut Context as *mut ErlNifEnv;
+        let mut ptr: *mut c_void = core::ptr::null_mut();
+        let found = unsafe {
+            enif_get_resource(env, handle.raw() as ERL_NIF_TERM, get_otm8009a_driver(), &mut ptr)
+        };
+
+        if found == 0 || ptr.is_null() {
+            return Err(NifError::BadArg);
+        }
+
+        // SAFETY: the resource type check above guarantees ptr holds a NifDriver
+        Ok(unsafe { &mut *(ptr as *mut NifDriver) })
+    }
+
+    /// The driver behind a handle, seen only as a `DisplayPanel`
+    fn get_panel<'a>(
+        ctx: &mut Context,
+        handle: Term,
+    ) -> NifResult<&'a mut impl DisplayPanel<Pixel = Rgb565, Error = NifDisplayError>> {
+        get_driver(ctx, handle)
+    }
+
+    /// `ok`, or `{error, Reason}` if the driver failed
+    fn reply(ctx: &mut Context, result: Result<(), NifDisplayError>) -> NifResult<usize> {
+        match result {
+            Ok(()) => make_atom("ok"),
+            Err(err) => error_reply(ctx, err),
+        }
+    }
+
+    /// `{error, Reason}` for a driver failure
+    ///
+    /// Invalid arguments still raise `badarg`. Reasons:
+    /// - `not_ready`, `comm_error`, `timeout`, `unsupported`
+    /// - `{wrong_panel_id, Id}` and `{unknown_panel, {Id1, Id2, Id3}}`
+    /// - `{illegal_transition, From, To}` with power mode atoms
+    /// - `{dsi, Opcode | undefined, Stage, Code}`, where `Stage` is
+    ///   `undefined`, `read_id`, `pixel_format`, `orientation`,
+    ///   `{power_up, Step}` or `{display_on, Step}`
+    /// - `{ltdc, Code}`
+    fn error_reply(ctx: &mut Context, err: NifDisplayError) -> NifResult<usize> {
+        let reason = match err {
+            DisplayError::InvalidConfig | DisplayError::InvalidCoordinates => return Err(NifError::BadArg),
+            DisplayError::NotReady => make_atom("not_ready")?,
+            DisplayError::CommError => make_atom("comm_error")?,
+            DisplayError::Timeout => make_atom("timeout")?,
+            DisplayError::Unsupported => make_atom("unsupported")?,
+            DisplayError::WrongPanelId(id) => {
+                make_tuple(ctx, &mut [make_atom("wrong_panel_id")?, make_int(id as i32)?])?
+            }
+            DisplayError::UnknownPanel(id) => {
+                let id = make_tuple(ctx, &mut [
+                    make_int(id.manufacturer as i32)?,
+                    make_int(id.version as i32)?,
+                    make_int(id.driver as i32)?,
+                ])?;
+                make_tuple(ctx, &mut [make_atom("unknown_panel")?, id])?
+            }
+            DisplayError::IllegalTransition { from, to } => make_tuple(ctx, &mut [
+                make_atom("illegal_transition")?,
+                make_atom(power_mode_name(from))?,
+                make_atom(power_mode_name(to))?,
+            ])?,
+            DisplayError::Dsi(DsiError { context: ErrorContext { opcode, stage }, error: PlatformError(code) }) => {
+                let opcode = match opcode {
+                    Some(opcode) => make_int(opcode as i32)?,
+                    None => make_atom("undefined")?,
+                };
+                let stage = match stage {
+                    Some(stage) => match stage.step() {
+                        Some(step) => make_tuple(ctx, &mut [make_atom(stage.name())?, make_int(step as i32)?])?,
+                        None => make_atom(stage.name())?,
+                    },
+                    None => make_atom("undefined")?,
+                };
+                make_tuple(ctx, &mut [make_atom("dsi")?, opcode, stage, make_int(code)?])?
+            }
+            DisplayError::Ltdc(PlatformError(code)) => {
+                make_tuple(ctx, &mut [make_atom("ltdc")?, make_int(code)?])?
+            }
+        };
+
+        let error = make_atom("error")?;
+        make_tuple(ctx, &mut [error, reason])
+    }
+
+    fn power_mode_name(mode: PowerMode) -> &'static str {
+        match mode {
+            PowerMode::Off => "off",
+            PowerMode::Sleep => "sleep",
+            PowerMode::DisplayOff => "display_off",
+            PowerMode::Normal => "normal",
+            PowerMode::PartialDisplay => "partial_display",
+            PowerMode::IdleMode => "idle_mode",
+        }
+    }
+
+    fn make_atom(name: &str) -> NifResult<usize> {
+        let index = AtomTable::from_global()
+            .ensure_atom_str(name)
+            .map_err(|_| NifError::OutOfMemory)?;
+        // Immediate terms never touch the heap
+        let mut heap = Heap { _private: [] };
+        Ok(Term::from_value(TermValue::Atom(index), &mut heap)?.raw())
+    }
+
+    fn make_int(value: i32) -> NifResult<usize> {
+        let mut heap = Heap { _private: [] };
+        Ok(Term::from_value(TermValue::int(value), &mut heap)?.raw())
+    }
+
+    fn make_tuple(ctx: &mut Context, elements: &mut [usize]) -> NifResult<usize> {
+        let tuple = unsafe { avmgl_make_tuple(ctx, elements.as_mut_ptr(), elements.len()) };
+        if tuple == 0 {
+            return Err(NifError::OutOfMemory);
+        }
+        Ok(tuple)
     }
 
     // Helper function to extract RGB color from term
-    #[allow(dead_code)]
-    fn extract_rgb_color(term: Term) -> NifResult<u32> {
+    fn extract_rgb_color(term: Term) -> NifResult<u16> {
         let value = term.to_value()?;
-        
+
         // Handle different color formats:
-        // - Integer: direct RGB value
+        // - Integer: direct RGB565 value
         // - Tuple: {R, G, B} format
         match value {
             TermValue::SmallInt(color) => {
-                if color < 0 {
-                    Err(NifError::BadArg)
-                } else {
-                    Ok(color as u32)
-                }
+                // Anything wider than RGB565 is rejected rather than truncated
+                u16::try_from(color).map_err(|_| NifError::BadArg)
             }
             TermValue::Tuple(elements) if elements.len() == 3 => {
                 let r = elements[0].as_int().ok_or(NifError::BadArg)?;
                 let g = elements[1].as_int().ok_or(NifError::BadArg)?;
                 let b = elements[2].as_int().ok_or(NifError::BadArg)?;
-                
+
                 // Validate RGB values
-                if r < 0 || r > 255 || g < 0 || g > 255 || b < 0 || b > 255 {
+                if !(0..=255).contains(&r) || !(0..=255).contains(&g) || !(0..=255).contains(&b) {
                     return Err(NifError::BadArg);
                 }
-                
+
                 // Convert to RGB565 format (assuming 16-bit color)
-                let rgb565 = ((r as u32 & 0xF8) << 8) | 
-                            ((g as u32 & 0xFC) << 3) | 
-                            ((b as u32 & 0xF8) >> 3);
+                let rgb565 = ((r as u16 & 0xF8) << 8) |
+                            ((g as u16 & 0xFC) << 3) |
+                            ((b as u16 & 0xF8) >> 3);
                 Ok(rgb565)
             }
             _ => Err(NifError::BadArg)
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,134 @@
+//! Color lookup tables for indexed pixel formats
+//!
+//! In `L8`, `Al44` and `Al88` layers each pixel is an index into a per-layer
+//! CLUT of up to 256 RGB888 colors. An `L8` framebuffer takes one byte per
+//! pixel, half the memory of RGB565, at the cost of a 256 color palette.
+
+use crate::traits::pixel::L8;
+
+/// Number of entries in an LTDC color lookup table
+pub const CLUT_SIZE: usize = 256;
+
+/// Up to 256 RGB888 colors, stored as `0x00RRGGBB` words in the order the
+/// LTDC loads them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette {
+    colors: [u32; CLUT_SIZE],
+    len: usize,
+}
+
+impl Palette {
+    /// Empty palette
+    pub const fn new() -> Self {
+        Self { colors: [0; CLUT_SIZE], len: 0 }
+    }
+
+    /// Build a palette from `(r, g, b)` triples
+    ///
+    /// Returns `None` if there are more than 256 colors.
+    pub fn from_rgb888(colors: &[(u8, u8, u8)]) -> Option<Self> {
+        let mut palette = Self::new();
+        for &(r, g, b) in colors {
+            palette.push(r, g, b)?;
+        }
+        Some(palette)
+    }
+
+    /// Build a palette from `0x00RRGGBB` words
+    ///
+    /// Returns `None` if there are more than 256 colors.
+    pub fn from_words(colors: &[u32]) -> Option<Self> {
+        let mut palette = Self::new();
+        palette.colors.get_mut(..colors.len())?
+            .iter_mut()
+            .zip(colors)
+            .for_each(|(entry, color)| *entry = color & 0x00FF_FFFF);
+        palette.len = colors.len();
+        Some(palette)
+    }
+
+    /// 256 shades of gray, black at index 0
+    pub fn grayscale() -> Self {
+        let mut palette = Self::new();
+        for level in 0..=255u8 {
+            palette.push(level, level, level);
+        }
+        palette
+    }
+
+    /// Palette for `RRRGGGBB` indices, see [`Palette::rgb332_index`]
+    pub fn rgb332() -> Self {
+        let mut palette = Self::new();
+        for index in 0..=255u8 {
+            let scale = |value: u8, max: u8| (value as u16 * 255 / max as u16) as u8;
+            palette.push(scale(index >> 5, 7), scale((index >> 2) & 0x07, 7), scale(index & 0x03, 3));
+        }
+        palette
+    }
+
+    /// Index of the closest color in the [`Palette::rgb332`] palette
+    pub const fn rgb332_index(r: u8, g: u8, b: u8) -> L8 {
+        L8((r & 0xE0) | ((g & 0xE0) >> 3) | (b >> 6))
+    }
+
+    /// Append a color, returning its index
+    ///
+    /// Returns `None` if the palette is full.
+    pub fn push(&mut self, r: u8, g: u8, b: u8) -> Option<L8> {
+        if self.len == CLUT_SIZE {
+            return None;
+        }
+        let index = self.len as u8;
+        self.colors[self.len] = rgb888(r, g, b);
+        self.len += 1;
+        Some(L8(index))
+    }
+
+    /// Replace the color at `index`, growing the palette to include it
+    pub fn set(&mut self, index: u8, r: u8, g: u8, b: u8) {
+        self.colors[index as usize] = rgb888(r, g, b);
+        self.len = self.len.max(index as usize + 1);
+    }
+
+    /// Get the color at `index` as `(r, g, b)`
+    pub fn get(&self, index: u8) -> Option<(u8, u8, u8)> {
+        let [_, r, g, b] = self.as_words().get(index as usize)?.to_be_bytes();
+        Some((r, g, b))
+    }
+
+    /// Find the entry closest to `(r, g, b)` by squared RGB distance
+    pub fn nearest(&self, r: u8, g: u8, b: u8) -> Option<L8> {
+        let distance = |&(_, color): &(usize, &u32)| {
+            let [_, pr, pg, pb] = color.to_be_bytes();
+            let delta = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+            delta(pr, r) + delta(pg, g) + delta(pb, b)
+        };
+        self.as_words().iter().enumerate()
+            .min_by_key(distance)
+            .map(|(index, _)| L8(index as u8))
+    }
+
+    /// Number of colors
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the colors as `0x00RRGGBB` words
+    pub fn as_words(&self) -> &[u32] {
+        &self.colors[..self.len]
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+const fn rgb888(r: u8, g: u8, b: u8) -> u32 {
+    ((r as u32) << 16) | ((g as u32) << 8) | b as u32
+}
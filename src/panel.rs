@@ -0,0 +1,142 @@
+//! Panel detection
+//!
+//! STM32F769I-DISCO boards ship with either an OTM8009A or, on later
+//! revisions, an NT35510. `PanelDriver::probe` reads the DCS ID registers and
+//! builds a `DsiPanelDriver` for the matching controller, so callers can
+//! drive whichever panel is fitted through a single type.
+
+use crate::dsi_panel::{DsiPanelDriver, PanelSpec};
+use crate::error::{DisplayError, DsiError, InitStage};
+use crate::otm8009a::defs::ColorFormat;
+use crate::otm8009a::driver::Otm8009aSpec;
+use crate::reset::NoPin;
+use crate::script::InitScript;
+use crate::traits::*;
+
+#[cfg(feature = "nt35510")]
+use crate::nt35510::driver::Nt35510Spec;
+
+/// DCS commands reading the MIPI ID registers
+const READ_ID1: u8 = 0xDA;
+const READ_ID2: u8 = 0xDB;
+const READ_ID3: u8 = 0xDC;
+
+/// Display controllers this crate can drive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Panel {
+    Otm8009a,
+    #[cfg(feature = "nt35510")]
+    Nt35510,
+}
+
+impl Panel {
+    /// Identify the controller behind `id`
+    pub fn from_id(id: PanelId) -> Option<Self> {
+        [
+            Panel::Otm8009a,
+            #[cfg(feature = "nt35510")]
+            Panel::Nt35510,
+        ]
+        .into_iter()
+        .find(|panel| panel.matches(id))
+    }
+
+    fn spec(self) -> &'static dyn PanelSpec {
+        match self {
+            Panel::Otm8009a => &Otm8009aSpec,
+            #[cfg(feature = "nt35510")]
+            Panel::Nt35510 => &Nt35510Spec,
+        }
+    }
+}
+
+impl PanelSpec for Panel {
+    fn id_register(&self) -> u8 {
+        self.spec().id_register()
+    }
+
+    fn id(&self) -> u8 {
+        self.spec().id()
+    }
+
+    fn dimensions(&self) -> (u16, u16) {
+        self.spec().dimensions()
+    }
+
+    fn power_up(&self) -> &'static InitScript {
+        self.spec().power_up()
+    }
+
+    fn display_on(&self) -> &'static InitScript {
+        self.spec().display_on()
+    }
+
+    fn supports(&self, format: ColorFormat) -> bool {
+        self.spec().supports(format)
+    }
+}
+
+/// Error of a `PanelDriver` on `D` and `L`, keeping their DSI and LTDC errors
+pub type PanelError<D, L> = DisplayError<<D as DsiInterface>::Error, <L as LtdcInterface>::Error>;
+
+/// Read RDID1..RDID3 from whatever panel is attached
+pub fn read_panel_id<D: DsiInterface>(dsi: &mut D) -> Result<PanelId, DsiError<D::Error>> {
+    let mut id = [0u8; 3];
+    for (byte, command) in id.iter_mut().zip([READ_ID1, READ_ID2, READ_ID3]) {
+        let mut value = [0u8; 1];
+        dsi.read_register(command, &mut value)
+            .map_err(|error| DsiError::command(command, error).during(InitStage::ReadId))?;
+        *byte = value[0];
+    }
+
+    Ok(PanelId { manufacturer: id[0], version: id[1], driver: id[2] })
+}
+
+/// Identify the attached panel
+pub fn detect<D: DsiInterface, LE>(dsi: &mut D) -> Result<Panel, DisplayError<D::Error, LE>> {
+    let id = read_panel_id(dsi).map_err(DisplayError::Dsi)?;
+    Panel::from_id(id).ok_or(DisplayError::UnknownPanel(id))
+}
+
+/// Driver for whichever supported panel is attached
+pub type PanelDriver<D, L, F, R = NoPin> = DsiPanelDriver<Panel, D, L, F, R>;
+
+impl<D, L, F> PanelDriver<D, L, F>
+where
+    D: DsiInterface,
+    L: LtdcInterface,
+    F: FramebufferInterface,
+{
+    /// Detect the attached panel and build a driver for it
+    pub fn probe(mut dsi: D, ltdc: L, framebuffer: F) -> Result<Self, PanelError<D, L>> {
+        let panel = detect(&mut dsi)?;
+        Ok(Self::build(panel, dsi, ltdc, framebuffer, None))
+    }
+}
+
+impl<D, L, F> PanelDriver<D, L, F>
+where
+    D: DsiInterface,
+    L: LtdcInterface,
+    F: FramebufferInterface,
+{
+    /// Detect the attached panel and build a driver for it, with brightness
+    /// going to the board backlight
+    pub fn probe_with_backlight(mut dsi: D, ltdc: L, framebuffer: F, backlight: &'static mut impl Backlight) -> Result<Self, PanelError<D, L>> {
+        let panel = detect(&mut dsi)?;
+        Ok(Self::build(panel, dsi, ltdc, framebuffer, Some(backlight)))
+    }
+}
+
+impl<D, L, F, R> PanelDriver<D, L, F, R>
+where
+    D: DsiInterface,
+    L: LtdcInterface,
+    F: FramebufferInterface,
+    R: OutputPin,
+{
+    /// Get the detected controller
+    pub fn panel(&self) -> Panel {
+        *self.spec()
+    }
+}
@@ -0,0 +1,259 @@
+//! Platform hooks for the NIF layer
+//!
+//! The NIF layer cannot know which board it is running on, so the DSI host,
+//! LTDC and framebuffer memory are reached through `extern "C"` functions that
+//! the AtomVM platform port provides, as declared in `c/avmgl_platform.h`.
+//! Each hook returns `0` on success and a non-zero status on failure.
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::framebuffer::SliceFramebuffer;
+use crate::traits::{pixel::{Rgb565, L8}, *};
+
+extern "C" {
+    fn avmgl_dsi_send_dcs_command(nb_params: usize, params: *const u8) -> i32;
+    fn avmgl_dsi_delay_ms(ms: u32);
+    fn avmgl_dsi_is_ready() -> bool;
+    fn avmgl_dsi_reset() -> i32;
+    fn avmgl_dsi_set_max_return_packet_size(size: u16) -> i32;
+    fn avmgl_dsi_dcs_read(command: u8, buffer: *mut u8, len: usize) -> i32;
+    fn avmgl_dsi_wait_for_te() -> i32;
+
+    fn avmgl_ltdc_configure_layer(layer: u8, config: *const LayerConfig) -> i32;
+    fn avmgl_ltdc_enable() -> i32;
+    fn avmgl_ltdc_disable() -> i32;
+    fn avmgl_ltdc_enable_layer(layer: u8) -> i32;
+    fn avmgl_ltdc_disable_layer(layer: u8) -> i32;
+    fn avmgl_ltdc_load_clut(layer: u8, colors: *const u32, len: usize) -> i32;
+    fn avmgl_ltdc_set_framebuffer(layer: u8, address: u32) -> i32;
+    fn avmgl_ltdc_get_dimensions(width: *mut u16, height: *mut u16);
+    fn avmgl_ltdc_wait_for_vblank() -> i32;
+
+    fn avmgl_backlight_set_enabled(enabled: bool) -> i32;
+    fn avmgl_backlight_set_duty(duty: u8) -> i32;
+
+    fn avmgl_framebuffer(len: *mut usize) -> *mut u16;
+}
+
+/// Non-zero status returned by a platform hook
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlatformError(pub i32);
+
+fn check(status: i32) -> Result<(), PlatformError> {
+    if status == 0 {
+        Ok(())
+    } else {
+        Err(PlatformError(status))
+    }
+}
+
+/// DSI host provided by the platform port
+#[derive(Debug, Default)]
+pub struct PlatformDsi;
+
+impl DsiInterface for PlatformDsi {
+    type Error = PlatformError;
+
+    fn send_dcs_command(&mut self, nb_params: usize, params: &[u8]) -> Result<(), Self::Error> {
+        check(unsafe { avmgl_dsi_send_dcs_command(nb_params, params.as_ptr()) })
+    }
+
+    fn delay_ms(&mut self, ms: u32) {
+        unsafe { avmgl_dsi_delay_ms(ms) }
+    }
+
+    fn is_ready(&self) -> bool {
+        unsafe { avmgl_dsi_is_ready() }
+    }
+
+    fn reset(&mut self) -> Result<(), Self::Error> {
+        check(unsafe { avmgl_dsi_reset() })
+    }
+
+    fn set_max_return_packet_size(&mut self, size: u16) -> Result<(), Self::Error> {
+        check(unsafe { avmgl_dsi_set_max_return_packet_size(size) })
+    }
+
+    fn dcs_read(&mut self, command: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        check(unsafe { avmgl_dsi_dcs_read(command, buffer.as_mut_ptr(), buffer.len()) })
+    }
+
+    fn wait_for_tearing_effect(&mut self) -> Result<(), Self::Error> {
+        check(unsafe { avmgl_dsi_wait_for_te() })
+    }
+}
+
+/// LTDC controller provided by the platform port
+#[derive(Debug, Default)]
+pub struct PlatformLtdc;
+
+impl LtdcInterface for PlatformLtdc {
+    type Error = PlatformError;
+
+    fn configure_layer(&mut self, layer: u8, config: LayerConfig) -> Result<(), Self::Error> {
+        check(unsafe { avmgl_ltdc_configure_layer(layer, &config) })
+    }
+
+    fn enable(&mut self) -> Result<(), Self::Error> {
+        check(unsafe { avmgl_ltdc_enable() })
+    }
+
+    fn disable(&mut self) -> Result<(), Self::Error> {
+        check(unsafe { avmgl_ltdc_disable() })
+    }
+
+    fn enable_layer(&mut self, layer: u8) -> Result<(), Self::Error> {
+        check(unsafe { avmgl_ltdc_enable_layer(layer) })
+    }
+
+    fn disable_layer(&mut self, layer: u8) -> Result<(), Self::Error> {
+        check(unsafe { avmgl_ltdc_disable_layer(layer) })
+    }
+
+    fn load_clut(&mut self, layer: u8, colors: &[u32]) -> Result<(), Self::Error> {
+        check(unsafe { avmgl_ltdc_load_clut(layer, colors.as_ptr(), colors.len()) })
+    }
+
+    fn set_framebuffer(&mut self, layer: u8, address: u32) -> Result<(), Self::Error> {
+        check(unsafe { avmgl_ltdc_set_framebuffer(layer, address) })
+    }
+
+    fn get_dimensions(&self) -> (u16, u16) {
+        let mut width = 0;
+        let mut height = 0;
+        unsafe { avmgl_ltdc_get_dimensions(&mut width, &mut height) };
+        (width, height)
+    }
+
+    fn wait_for_vblank(&mut self) -> Result<(), Self::Error> {
+        check(unsafe { avmgl_ltdc_wait_for_vblank() })
+    }
+}
+
+/// PWM backlight driven by the platform port
+pub struct PlatformBacklight;
+
+impl Backlight for PlatformBacklight {
+    type Error = PlatformError;
+
+    fn set_enabled(&mut self, enabled: bool) -> Result<(), Self::Error> {
+        check(unsafe { avmgl_backlight_set_enabled(enabled) })
+    }
+
+    fn set_level(&mut self, level: u8) -> Result<(), Self::Error> {
+        check(unsafe { avmgl_backlight_set_duty(level) })
+    }
+}
+
+/// Set while a framebuffer over the platform memory is alive
+static FRAMEBUFFER_TAKEN: AtomicBool = AtomicBool::new(false);
+
+/// Exclusive use of the platform framebuffer memory, given back on drop
+#[derive(Debug)]
+struct Claim;
+
+impl Claim {
+    fn take() -> Option<Self> {
+        FRAMEBUFFER_TAKEN.compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .ok()
+            .map(|_| Claim)
+    }
+}
+
+impl Drop for Claim {
+    fn drop(&mut self) {
+        FRAMEBUFFER_TAKEN.store(false, Ordering::Release);
+    }
+}
+
+/// Framebuffer living in memory handed out by the platform port
+///
+/// Only one exists at a time. Dropping it, e.g. with the NIF resource that
+/// owns the driver, lets the memory be taken again.
+#[derive(Debug)]
+pub struct PlatformFramebuffer<P: PixelType = Rgb565> {
+    buffer: SliceFramebuffer<P>,
+    _claim: Claim,
+}
+
+impl<P: PixelType> FramebufferInterface for PlatformFramebuffer<P> {
+    type Pixel = P;
+
+    fn fill_rect(&mut self, x: u16, y: u16, width: u16, height: u16, color: P) {
+        self.buffer.fill_rect(x, y, width, height, color)
+    }
+
+    fn set_pixel(&mut self, x: u16, y: u16, color: P) {
+        self.buffer.set_pixel(x, y, color)
+    }
+
+    fn clear(&mut self, color: P) {
+        self.buffer.clear(color)
+    }
+
+    fn get_dimensions(&self) -> (u16, u16) {
+        self.buffer.get_dimensions()
+    }
+
+    fn get_row(&self, y: u16) -> Option<&[P]> {
+        self.buffer.get_row(y)
+    }
+
+    fn write_row(&mut self, x: u16, y: u16, pixels: &[P]) {
+        self.buffer.write_row(x, y, pixels)
+    }
+
+    fn get_buffer_ptr(&self) -> *const P {
+        self.buffer.get_buffer_ptr()
+    }
+
+    fn get_buffer_size(&self) -> usize {
+        self.buffer.get_buffer_size()
+    }
+
+    fn get_pitch(&self) -> usize {
+        self.buffer.get_pitch()
+    }
+}
+
+/// Claim the platform memory, or `None` while it is already taken
+fn claim_memory() -> Option<(Claim, *mut u16, usize)> {
+    let claim = Claim::take()?;
+    let mut len = 0;
+    let ptr = unsafe { avmgl_framebuffer(&mut len) };
+    if ptr.is_null() {
+        return None;
+    }
+    Some((claim, ptr, len))
+}
+
+/// Claim the platform framebuffer for a `width` x `height` display
+///
+/// The memory (typically external SDRAM) is owned by the platform and stays
+/// valid for the lifetime of the program. Returns `None` when the platform has
+/// no buffer, it is too small, or it is already taken.
+pub fn take_framebuffer(width: u16, height: u16) -> Option<PlatformFramebuffer> {
+    let (claim, ptr, len) = claim_memory()?;
+
+    // The claim makes this the only slice over the memory
+    let buffer = unsafe { core::slice::from_raw_parts_mut(ptr, len) };
+    let buffer = SliceFramebuffer::from_u16(buffer, width, height, width as usize)?;
+    Some(PlatformFramebuffer { buffer, _claim: claim })
+}
+
+/// 8-bit indexed framebuffer in the same platform memory
+pub type IndexedFramebuffer = PlatformFramebuffer<L8>;
+
+/// Claim the platform framebuffer as one byte per pixel, for an `L8` layer
+/// whose colors come from a CLUT
+///
+/// Needs half the memory of [`take_framebuffer`], and shares its claim.
+/// Returns `None` when the platform has no buffer, it is too small, or it is
+/// already taken.
+pub fn take_indexed_framebuffer(width: u16, height: u16) -> Option<IndexedFramebuffer> {
+    let (claim, ptr, len) = claim_memory()?;
+
+    let buffer = unsafe { core::slice::from_raw_parts_mut(ptr as *mut u8, len * 2) };
+    let buffer = SliceFramebuffer::from_u8(buffer, width, height, width as usize)?;
+    Some(PlatformFramebuffer { buffer, _claim: claim })
+}
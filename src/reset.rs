@@ -0,0 +1,40 @@
+//! Hardware reset
+//!
+//! Panels are reset by pulsing their XRES line low. Boards that wire it to a
+//! GPIO hand the pin to the driver; `NoPin` stands in when they don't, and
+//! the driver falls back to resetting the DSI host only.
+
+use core::convert::Infallible;
+
+use crate::traits::{DsiInterface, OutputPin};
+
+/// Placeholder for drivers without a reset pin
+#[derive(Debug)]
+pub enum NoPin {}
+
+impl OutputPin for NoPin {
+    type Error = Infallible;
+
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        match *self {}
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        match *self {}
+    }
+}
+
+/// Hold `pin` low for `pulse_ms`, then release it and wait `release_ms` for
+/// the panel to finish its reset
+pub fn pulse_reset<P: OutputPin, D: DsiInterface>(
+    pin: &mut P,
+    dsi: &mut D,
+    pulse_ms: u32,
+    release_ms: u32,
+) -> Result<(), P::Error> {
+    pin.set_low()?;
+    dsi.delay_ms(pulse_ms);
+    pin.set_high()?;
+    dsi.delay_ms(release_ms);
+    Ok(())
+}
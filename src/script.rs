@@ -0,0 +1,98 @@
+//! Panel init scripts
+//!
+//! A bring-up sequence is a `const` slice of `InitStep`s that `run_script`
+//! plays back against any `DsiInterface`. Keeping the sequence as data lets it
+//! be reviewed against the vendor reference, swapped per panel and tested
+//! without a driver.
+//!
+//! The same scripts can be played back on a MIPI DBI `DisplayBus`, where the
+//! command byte and its parameters are sent as separate transfers.
+
+use crate::error::{BusError, DsiError};
+use crate::traits::{DisplayBus, DsiInterface};
+
+/// DCS command used by ORISE controllers to set the low byte of the next
+/// vendor register address
+pub const ORISE_ADDRESS_SHIFT: u8 = 0x00;
+
+/// One step of an init script
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InitStep {
+    /// DCS write; the command byte followed by its parameters
+    Write(&'static [u8]),
+    /// ORISE vendor register write: set the address shift, then write the
+    /// command byte followed by its parameters
+    Shifted(u8, &'static [u8]),
+    /// Wait for the given number of milliseconds
+    Delay(u32),
+}
+
+/// Sequence of init steps
+pub type InitScript = [InitStep];
+
+/// Send a command byte followed by its parameters
+pub fn write_payload<D: DsiInterface>(dsi: &mut D, payload: &[u8]) -> Result<(), D::Error> {
+    dsi.send_dcs_command(payload.len().saturating_sub(1), payload)
+}
+
+/// A failed script step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StepError<E> {
+    /// Index of the step in the script
+    pub step: usize,
+    pub error: BusError<E>,
+}
+
+/// Play back `script` on `dsi`, stopping at the first failed write
+pub fn run_script<D: DsiInterface>(dsi: &mut D, script: &InitScript) -> Result<(), StepError<D::Error>> {
+    for (index, step) in script.iter().enumerate() {
+        let write = |dsi: &mut D, payload: &[u8]| {
+            write_payload(dsi, payload).map_err(|error| StepError {
+                step: index,
+                error: DsiError::command(payload.first().copied().unwrap_or_default(), error),
+            })
+        };
+        match *step {
+            InitStep::Write(payload) => write(dsi, payload)?,
+            InitStep::Shifted(shift, payload) => {
+                write(dsi, &[ORISE_ADDRESS_SHIFT, shift])?;
+                write(dsi, payload)?;
+            }
+            InitStep::Delay(ms) => dsi.delay_ms(ms),
+        }
+    }
+    Ok(())
+}
+
+/// Send a command byte followed by its parameters over a DBI bus
+pub fn write_bus_payload<B: DisplayBus>(bus: &mut B, payload: &[u8]) -> Result<(), B::Error> {
+    let Some((&command, params)) = payload.split_first() else {
+        return Ok(());
+    };
+    bus.write_command(command)?;
+    if !params.is_empty() {
+        bus.write_data(params)?;
+    }
+    Ok(())
+}
+
+/// Play back `script` on a DBI bus, stopping at the first failed write
+pub fn run_bus_script<B: DisplayBus>(bus: &mut B, script: &InitScript) -> Result<(), StepError<B::Error>> {
+    for (index, step) in script.iter().enumerate() {
+        let write = |bus: &mut B, payload: &[u8]| {
+            write_bus_payload(bus, payload).map_err(|error| StepError {
+                step: index,
+                error: BusError::command(payload.first().copied().unwrap_or_default(), error),
+            })
+        };
+        match *step {
+            InitStep::Write(payload) => write(bus, payload)?,
+            InitStep::Shifted(shift, payload) => {
+                write(bus, &[ORISE_ADDRESS_SHIFT, shift])?;
+                write(bus, payload)?;
+            }
+            InitStep::Delay(ms) => bus.delay_ms(ms),
+        }
+    }
+    Ok(())
+}
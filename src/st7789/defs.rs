@@ -0,0 +1,138 @@
+//! Constants and definitions for the ST7789 display driver
+
+/// Display dimensions
+pub const LCD_WIDTH: u16 = 320;
+pub const LCD_HEIGHT: u16 = 240;
+
+/// Color format constants
+///
+/// The serial interface carries 16-bit and 18-bit pixels only.
+pub const ST7789_FORMAT_RGB565: u32 = crate::dcs::FORMAT_RGB565;
+pub const ST7789_FORMAT_RGB666: u32 = crate::dcs::FORMAT_RGB666;
+
+/// Orientation constants
+pub const ST7789_ORIENTATION_PORTRAIT: u32 = 0;
+pub const ST7789_ORIENTATION_LANDSCAPE: u32 = 1;
+pub const ST7789_ORIENTATION_PORTRAIT_FLIPPED: u32 = 2;
+pub const ST7789_ORIENTATION_LANDSCAPE_FLIPPED: u32 = 3;
+
+/// ST7789 specific DCS commands
+pub mod commands {
+    /// Standard DCS commands
+    pub const SOFT_RESET: u8 = 0x01;
+    pub const SLEEP_OUT: u8 = 0x11;
+    pub const NORMAL_MODE_ON: u8 = 0x13;
+    pub const INVERSION_ON: u8 = 0x21;
+    pub const DISPLAY_ON: u8 = 0x29;
+    pub const DISPLAY_OFF: u8 = 0x28;
+    pub const SLEEP_IN: u8 = 0x10;
+    pub const NOP: u8 = 0x00;
+
+    /// Memory access control
+    pub const SET_MEMORY_ACCESS_CONTROL: u8 = 0x36;
+    pub const SET_PIXEL_FORMAT: u8 = 0x3A;
+    pub const SET_COLUMN_ADDRESS: u8 = 0x2A;
+    pub const SET_PAGE_ADDRESS: u8 = 0x2B;
+    pub const WRITE_MEMORY_START: u8 = 0x2C;
+
+    /// Brightness
+    pub const WRITE_DISPLAY_BRIGHTNESS: u8 = 0x51;
+    pub const WRITE_CTRL_DISPLAY: u8 = 0x53;
+}
+
+/// Command data sequences for initialization
+pub mod init_sequences {
+    /// Porch setting
+    pub const CMD_PORCH: [u8; 6] = [0xB2, 0x0C, 0x0C, 0x00, 0x33, 0x33];
+    /// Gate control: VGH 13.26V, VGL -10.43V
+    pub const CMD_GATE_CTRL: [u8; 2] = [0xB7, 0x35];
+    /// VCOM 0.725V
+    pub const CMD_VCOM: [u8; 2] = [0xBB, 0x19];
+    /// LCM control
+    pub const CMD_LCM_CTRL: [u8; 2] = [0xC0, 0x2C];
+    /// VDV and VRH from the commands below
+    pub const CMD_VDV_VRH_ENABLE: [u8; 2] = [0xC2, 0x01];
+    /// VRH 4.45V
+    pub const CMD_VRH: [u8; 2] = [0xC3, 0x12];
+    /// VDV 0V
+    pub const CMD_VDV: [u8; 2] = [0xC4, 0x20];
+    /// Frame rate 60Hz in normal mode
+    pub const CMD_FRAME_RATE: [u8; 2] = [0xC6, 0x0F];
+    /// AVDD 6.8V, AVCL -4.8V, VDDS 2.3V
+    pub const CMD_POWER_CTRL1: [u8; 3] = [0xD0, 0xA4, 0xA1];
+    /// Positive and negative gamma correction
+    pub const CMD_GAMMA_POSITIVE: [u8; 15] = [
+        0xE0, 0xD0, 0x04, 0x0D, 0x11, 0x13, 0x2B, 0x3F, 0x54, 0x4C, 0x18, 0x0D, 0x0B, 0x1F, 0x23,
+    ];
+    pub const CMD_GAMMA_NEGATIVE: [u8; 15] = [
+        0xE1, 0xD0, 0x04, 0x0C, 0x11, 0x13, 0x2C, 0x3F, 0x44, 0x51, 0x2F, 0x1F, 0x1F, 0x20, 0x23,
+    ];
+
+    /// Color format commands
+    pub const CMD_RGB565: [u8; 2] = [0x3A, 0x55];
+    pub const CMD_RGB666: [u8; 2] = [0x3A, 0x66];
+
+    /// Orientation commands (RGB panel)
+    pub const CMD_PORTRAIT: [u8; 2] = [0x36, 0x00];
+    pub const CMD_LANDSCAPE: [u8; 2] = [0x36, 0x60];
+    pub const CMD_PORTRAIT_FLIPPED: [u8; 2] = [0x36, 0xC0];
+    pub const CMD_LANDSCAPE_FLIPPED: [u8; 2] = [0x36, 0xA0];
+}
+
+/// Single byte commands
+pub mod single_commands {
+    pub const NOP: [u8; 1] = [0x00];
+    pub const SOFT_RESET: [u8; 1] = [0x01];
+    pub const SLEEP_OUT: [u8; 1] = [0x11];
+    pub const SLEEP_IN: [u8; 1] = [0x10];
+    pub const NORMAL_MODE_ON: [u8; 1] = [0x13];
+    pub const INVERSION_ON: [u8; 1] = [0x21];
+    pub const DISPLAY_ON: [u8; 1] = [0x29];
+    pub const DISPLAY_OFF: [u8; 1] = [0x28];
+}
+
+/// Init scripts played back by `ST7789Driver::init`
+///
+/// Pixel format and orientation depend on the caller and are sent between
+/// `POWER_UP` and `DISPLAY_ON`.
+pub mod scripts {
+    use super::{init_sequences::*, single_commands, timing};
+    use crate::script::InitStep::{self, *};
+
+    /// Software reset, power settings, gamma and sleep out
+    pub const POWER_UP: &[InitStep] = &[
+        Write(&single_commands::SOFT_RESET),
+        Delay(timing::RESET_DELAY_MS),
+
+        Write(&CMD_PORCH),
+        Write(&CMD_GATE_CTRL),
+        Write(&CMD_VCOM),
+        Write(&CMD_LCM_CTRL),
+        Write(&CMD_VDV_VRH_ENABLE),
+        Write(&CMD_VRH),
+        Write(&CMD_VDV),
+        Write(&CMD_FRAME_RATE),
+        Write(&CMD_POWER_CTRL1),
+        Write(&CMD_GAMMA_POSITIVE),
+        Write(&CMD_GAMMA_NEGATIVE),
+
+        Write(&single_commands::SLEEP_OUT),
+        Delay(timing::SLEEP_OUT_DELAY_MS),
+    ];
+
+    /// Inversion and normal mode on, display on
+    ///
+    /// ST7789 IPS modules are built for inverted colors.
+    pub const DISPLAY_ON: &[InitStep] = &[
+        Write(&single_commands::INVERSION_ON),
+        Write(&single_commands::NORMAL_MODE_ON),
+        Write(&single_commands::DISPLAY_ON),
+        Delay(timing::DISPLAY_ON_DELAY_MS),
+    ];
+}
+
+/// Timing parameters for display initialization
+pub use crate::dbi::timing;
+
+/// Errors returned by `ST7789Driver`, keeping the bus error
+pub type St7789Error<E> = crate::error::DbiError<E>;
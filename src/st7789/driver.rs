@@ -0,0 +1,40 @@
+//! ST7789 Driver Implementation
+//! 
+//! The panel keeps its own frame memory, so drawing goes straight over any
+//! MIPI DBI `DisplayBus` rather than through a framebuffer. Everything but
+//! the vendor registers and MADCTL values lives in `DbiPanelDriver`.
+
+use crate::dbi_panel::{DbiPanelDriver, DbiPanelSpec};
+use crate::st7789::defs::*;
+use crate::otm8009a::defs::Orientation;
+use crate::script::InitScript;
+
+/// ST7789 specifics for `DbiPanelDriver`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct St7789Spec;
+
+impl DbiPanelSpec for St7789Spec {
+    fn dimensions(&self) -> (u16, u16) {
+        (LCD_WIDTH, LCD_HEIGHT)
+    }
+
+    fn power_up(&self) -> &'static InitScript {
+        scripts::POWER_UP
+    }
+
+    fn display_on(&self) -> &'static InitScript {
+        scripts::DISPLAY_ON
+    }
+
+    fn madctl(&self, orientation: Orientation) -> u8 {
+        let [_, madctl] = match orientation {
+            Orientation::Portrait => init_sequences::CMD_PORTRAIT,
+            Orientation::Landscape => init_sequences::CMD_LANDSCAPE,
+            Orientation::PortraitFlipped => init_sequences::CMD_PORTRAIT_FLIPPED,
+            Orientation::LandscapeFlipped => init_sequences::CMD_LANDSCAPE_FLIPPED,
+        };
+        madctl
+    }
+}
+
+pub type ST7789Driver<B> = DbiPanelDriver<St7789Spec, B>;
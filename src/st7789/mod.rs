@@ -0,0 +1,11 @@
+//! ST7789 Display Driver Module
+//! 
+//! This module provides support for the ST7789 display controller found on
+//! 240x320 IPS SPI modules used with ESP32-class AtomVM boards.
+
+pub mod defs;
+pub mod driver;
+
+// Re-export the main types and functions
+pub use driver::{ST7789Driver, St7789Spec};
+pub use defs::*;
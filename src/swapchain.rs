@@ -0,0 +1,189 @@
+//! Double and triple buffering
+//!
+//! `SwapChain` rotates two or three framebuffers of the same size. Drawing
+//! goes to the back buffer and `flip` hands it to the LTDC, so a frame is
+//! never shown half drawn. Since the new back buffer still holds an older
+//! frame, the chain can track what was drawn since and copy those regions
+//! over from the front buffer, letting callers redraw only what changed.
+
+use crate::traits::*;
+
+/// Inclusive bounding box of the pixels drawn into a buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Damage {
+    x0: u16,
+    y0: u16,
+    x1: u16,
+    y1: u16,
+}
+
+impl Damage {
+    fn union(this: Option<Self>, other: Self) -> Self {
+        match this {
+            Some(damage) => Damage {
+                x0: damage.x0.min(other.x0),
+                y0: damage.y0.min(other.y0),
+                x1: damage.x1.max(other.x1),
+                y1: damage.y1.max(other.y1),
+            },
+            None => other,
+        }
+    }
+}
+
+/// `N` framebuffers taking turns being scanned out
+#[derive(Debug)]
+pub struct SwapChain<F: FramebufferInterface, const N: usize> {
+    buffers: [F; N],
+    front: usize,
+    /// Regions each buffer is missing compared to the newest frame
+    stale: [Option<Damage>; N],
+    restore: bool,
+}
+
+impl<F: FramebufferInterface, const N: usize> SwapChain<F, N> {
+    /// Rotate `buffers`, showing the first one
+    ///
+    /// Returns `None` unless there are two or three buffers of the same size
+    /// and layout.
+    pub fn new(buffers: [F; N]) -> Option<Self> {
+        if !(2..=3).contains(&N) {
+            return None;
+        }
+        let first = &buffers[0];
+        if buffers.iter().any(|buffer| {
+            buffer.get_dimensions() != first.get_dimensions() || buffer.get_pitch() != first.get_pitch()
+        }) {
+            return None;
+        }
+
+        Some(Self { buffers, front: 0, stale: [None; N], restore: false })
+    }
+
+    /// Copy regions drawn since a buffer was last shown into it after each
+    /// flip, so only what changed needs to be redrawn
+    pub fn set_restore_back(&mut self, restore: bool) {
+        self.restore = restore;
+    }
+
+    /// Get the buffer being scanned out
+    pub fn front(&self) -> &F {
+        &self.buffers[self.front]
+    }
+
+    /// Get the buffer being drawn into
+    pub fn back(&self) -> &F {
+        &self.buffers[self.back_index()]
+    }
+
+    /// Release the buffers
+    pub fn into_inner(self) -> [F; N] {
+        self.buffers
+    }
+
+    fn back_index(&self) -> usize {
+        (self.front + 1) % N
+    }
+
+    fn back_mut(&mut self) -> &mut F {
+        let back = self.back_index();
+        &mut self.buffers[back]
+    }
+
+    /// Record that the back buffer changed inside `(x, y, width, height)`
+    fn damage(&mut self, x: u16, y: u16, width: u16, height: u16) {
+        let (fb_width, fb_height) = self.back().get_dimensions();
+        let x1 = x.saturating_add(width).min(fb_width);
+        let y1 = y.saturating_add(height).min(fb_height);
+        if x >= x1 || y >= y1 {
+            return;
+        }
+
+        let drawn = Damage { x0: x, y0: y, x1: x1 - 1, y1: y1 - 1 };
+        let back = self.back_index();
+        for (index, stale) in self.stale.iter_mut().enumerate() {
+            if index != back {
+                *stale = Some(Damage::union(*stale, drawn));
+            }
+        }
+    }
+}
+
+impl<F: FramebufferInterface, const N: usize> FramebufferInterface for SwapChain<F, N> {
+    type Pixel = F::Pixel;
+
+    fn fill_rect(&mut self, x: u16, y: u16, width: u16, height: u16, color: F::Pixel) {
+        self.damage(x, y, width, height);
+        self.back_mut().fill_rect(x, y, width, height, color);
+    }
+
+    fn set_pixel(&mut self, x: u16, y: u16, color: F::Pixel) {
+        self.damage(x, y, 1, 1);
+        self.back_mut().set_pixel(x, y, color);
+    }
+
+    fn clear(&mut self, color: F::Pixel) {
+        self.damage(0, 0, u16::MAX, u16::MAX);
+        self.back_mut().clear(color);
+    }
+
+    fn get_dimensions(&self) -> (u16, u16) {
+        self.front().get_dimensions()
+    }
+
+    fn get_row(&self, y: u16) -> Option<&[F::Pixel]> {
+        self.front().get_row(y)
+    }
+
+    fn write_row(&mut self, x: u16, y: u16, pixels: &[F::Pixel]) {
+        self.damage(x, y, pixels.len().min(u16::MAX as usize) as u16, 1);
+        self.back_mut().write_row(x, y, pixels);
+    }
+
+    fn get_buffer_ptr(&self) -> *const F::Pixel {
+        self.front().get_buffer_ptr()
+    }
+
+    fn get_buffer_size(&self) -> usize {
+        self.front().get_buffer_size()
+    }
+
+    fn get_pitch(&self) -> usize {
+        self.front().get_pitch()
+    }
+}
+
+impl<F: FramebufferInterface, const N: usize> PageFlip for SwapChain<F, N> {
+    fn buffer_count(&self) -> usize {
+        N
+    }
+
+    fn flip(&mut self) {
+        self.front = self.back_index();
+    }
+
+    fn restore_back(&mut self) {
+        let back = self.back_index();
+        let Some(stale) = self.stale[back].take() else {
+            return;
+        };
+        if !self.restore {
+            return;
+        }
+
+        // The front buffer holds the newest frame
+        let (front, back) = if self.front < back {
+            let (head, tail) = self.buffers.split_at_mut(back);
+            (&head[self.front], &mut tail[0])
+        } else {
+            let (head, tail) = self.buffers.split_at_mut(self.front);
+            (&tail[0], &mut head[back])
+        };
+
+        for y in stale.y0..=stale.y1 {
+            if let Some(row) = front.get_row(y) {
+                back.write_row(stale.x0, y, &row[stale.x0 as usize..=stale.x1 as usize]);
+            }
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//! Tests for board backlights
+
+#[cfg(test)]
+mod tests {
+    use crate::backlight::GpioBacklight;
+    use crate::otm8009a::defs::*;
+    use crate::testing::mocks::*;
+    use crate::traits::*;
+
+    fn backlit_driver(backlight: &MockBacklight) -> MockDriver {
+        new_panel_driver(MockDsiInterface::new(), Orientation::Landscape, Some(backlight.clone()))
+    }
+
+    #[test]
+    fn test_perceptual_curve() {
+        let backlight = MockBacklight::new();
+
+        assert_eq!(backlight.perceptual_level(0), 0);
+        assert_eq!(backlight.perceptual_level(1), 1);
+        assert_eq!(backlight.perceptual_level(128), 65);
+        assert_eq!(backlight.perceptual_level(255), 255);
+    }
+
+    #[test]
+    fn test_brightness_goes_to_backlight() {
+        let backlight = MockBacklight::new();
+        let mut driver = backlit_driver(&backlight);
+        driver.init(OTM8009A_FORMAT_RGB565, OTM8009A_ORIENTATION_LANDSCAPE).unwrap();
+
+        assert_eq!(backlight.state().switches, [true]);
+        assert_eq!(backlight.level(), Some(backlight.perceptual_level(0x7F)));
+
+        driver.dsi_mut().clear_history();
+        driver.set_brightness(255).unwrap();
+        driver.fade_brightness(0, 20).unwrap();
+
+        assert_eq!(backlight.state().levels[1..], [255, 65, 0]);
+        assert!(driver.dsi().commands_sent.iter().all(|command| command.params[0] != commands::WRITE_DISPLAY_BRIGHTNESS));
+        assert_eq!(driver.brightness(), 0);
+    }
+
+    #[test]
+    fn test_backlight_follows_display_power() {
+        let backlight = MockBacklight::new();
+        let mut driver = backlit_driver(&backlight);
+        driver.init(OTM8009A_FORMAT_RGB565, OTM8009A_ORIENTATION_LANDSCAPE).unwrap();
+
+        driver.enter_sleep().unwrap();
+        assert_eq!(backlight.state().switches.last(), Some(&false));
+        driver.exit_sleep().unwrap();
+        assert_eq!(backlight.state().switches.last(), Some(&true));
+
+        backlight.set_should_fail(true);
+        assert_eq!(driver.set_brightness(10), Err(Otm8009aError::CommError));
+    }
+
+    #[test]
+    fn test_gpio_backlight() {
+        let mut backlight = GpioBacklight::new(MockOutputPin::new());
+
+        backlight.set_level(0).unwrap();
+        backlight.set_level(1).unwrap();
+        backlight.set_enabled(false).unwrap();
+        assert_eq!(backlight.perceptual_level(1), 1);
+
+        assert_eq!(backlight.into_inner().states, vec![false, true, false]);
+    }
+}
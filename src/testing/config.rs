@@ -0,0 +1,133 @@
+//! Tests for the typed display configuration
+
+#[cfg(test)]
+mod tests {
+    use crate::otm8009a::defs::*;
+    use crate::testing::mocks::*;
+
+    #[test]
+    fn test_raw_values_parse() {
+        assert_eq!(Orientation::from_u32(OTM8009A_ORIENTATION_PORTRAIT_FLIPPED), Some(Orientation::PortraitFlipped));
+        assert_eq!(Orientation::from_u32(4), None);
+        assert_eq!(ColorFormat::from_u32(OTM8009A_FORMAT_RGB666), Some(ColorFormat::Rgb666));
+        assert_eq!(ColorFormat::from_u32(0x56), None);
+    }
+
+    #[test]
+    fn test_presets_match_orientation() {
+        let landscape = presets::STANDARD_LANDSCAPE;
+        assert_eq!(landscape.dimensions(), None);
+        assert_eq!(landscape.dimensions_on((LCD_WIDTH, LCD_HEIGHT)), Some((LCD_WIDTH, LCD_HEIGHT)));
+        assert_eq!(landscape.power_mode(), PowerMode::Normal);
+        assert_eq!(DisplayConfig::default(), landscape);
+
+        let portrait = presets::STANDARD_PORTRAIT;
+        assert_eq!(portrait.dimensions_on((LCD_WIDTH, LCD_HEIGHT)), Some((LCD_HEIGHT, LCD_WIDTH)));
+        assert_eq!(portrait.orientation(), Orientation::Portrait);
+    }
+
+    #[test]
+    fn test_mismatched_dimensions_rejected() {
+        let config = DisplayConfig::with_dimensions(LCD_HEIGHT, LCD_WIDTH, Orientation::PortraitFlipped, ColorFormat::Rgb888).unwrap();
+        assert_eq!(config.dimensions_on((LCD_WIDTH, LCD_HEIGHT)), Some((LCD_HEIGHT, LCD_WIDTH)));
+
+        assert_eq!(DisplayConfig::with_dimensions(LCD_HEIGHT, LCD_WIDTH, Orientation::Landscape, ColorFormat::Rgb565), None);
+        assert_eq!(DisplayConfig::with_dimensions(LCD_WIDTH, LCD_HEIGHT, Orientation::Portrait, ColorFormat::Rgb565), None);
+        assert_eq!(DisplayConfig::with_dimensions(0, 0, Orientation::Landscape, ColorFormat::Rgb565), None);
+
+        // Other panels have other sizes, only the driver knows its own
+        let qvga = DisplayConfig::with_dimensions(320, 240, Orientation::Landscape, ColorFormat::Rgb565).unwrap();
+        assert_eq!(qvga.dimensions_on((320, 240)), Some((320, 240)));
+        assert_eq!(qvga.dimensions_on((LCD_WIDTH, LCD_HEIGHT)), None);
+        let square = DisplayConfig::with_dimensions(240, 240, Orientation::Portrait, ColorFormat::Rgb565);
+        assert!(square.is_some());
+    }
+
+    #[test]
+    fn test_off_power_mode_rejected() {
+        assert_eq!(presets::STANDARD_LANDSCAPE.with_power_mode(PowerMode::Off), None);
+
+        let config = presets::STANDARD_LANDSCAPE.with_power_mode(PowerMode::IdleMode).unwrap();
+        assert_eq!(config.power_mode(), PowerMode::IdleMode);
+    }
+
+    #[test]
+    fn test_init_with_preset() {
+        let driver = init_driver(presets::STANDARD_PORTRAIT);
+
+        assert!(driver.is_initialized());
+        assert_eq!(driver.get_dimensions(), (LCD_HEIGHT, LCD_WIDTH));
+        assert_eq!(driver.power_mode(), PowerMode::Normal);
+
+        let sent: Vec<&[u8]> = driver.dsi().commands_sent.iter().map(|command| command.params.as_slice()).collect();
+        assert!(sent.contains(&&init_sequences::CMD_RGB565[..]));
+        assert!(sent.contains(&&init_sequences::CMD_PORTRAIT[..]));
+    }
+
+    #[test]
+    fn test_framebuffer_must_match_config() {
+        let mut driver = new_driver();
+
+        // A landscape buffer is too short for portrait scan out
+        assert_eq!(driver.init_with(presets::STANDARD_PORTRAIT), Err(Otm8009aError::InvalidConfig));
+        assert!(!driver.is_initialized());
+        assert_eq!(driver.dsi().command_count(), 0);
+
+        // The panel is 800x480, whatever the config asks for
+        let qvga = DisplayConfig::with_dimensions(320, 240, Orientation::Landscape, ColorFormat::Rgb565).unwrap();
+        assert_eq!(driver.init_with(qvga), Err(Otm8009aError::InvalidConfig));
+        let full = DisplayConfig::with_dimensions(LCD_WIDTH, LCD_HEIGHT, Orientation::Landscape, ColorFormat::Rgb565).unwrap();
+        driver.init_with(full).unwrap();
+    }
+
+    #[test]
+    fn test_init_with_power_mode() {
+        let mut driver = new_driver();
+        let config = DisplayConfig::new(Orientation::Landscape, ColorFormat::Rgb888)
+            .with_power_mode(PowerMode::Sleep)
+            .unwrap();
+        driver.init_with(config).unwrap();
+
+        assert!(driver.is_initialized());
+        assert_eq!(driver.power_mode(), PowerMode::Sleep);
+        assert_eq!(driver.dsi().commands_sent.last().unwrap().params, [0x10]);
+    }
+
+    #[test]
+    fn test_typed_orientation() {
+        let mut driver = new_driver();
+        assert_eq!(driver.set_orientation(Orientation::Portrait), Err(Otm8009aError::NotReady));
+
+        driver.init_with(DisplayConfig::default()).unwrap();
+        driver.dsi_mut().clear_history();
+        driver.set_orientation(Orientation::LandscapeFlipped).unwrap();
+        assert_eq!(driver.get_dimensions(), (LCD_WIDTH, LCD_HEIGHT));
+        assert_eq!(driver.dsi().commands_sent[0].params, init_sequences::CMD_LANDSCAPE_FLIPPED);
+
+        // The landscape framebuffer cannot be scanned out in portrait
+        driver.dsi_mut().clear_history();
+        assert_eq!(driver.set_orientation(Orientation::PortraitFlipped), Err(Otm8009aError::InvalidConfig));
+        assert_eq!(driver.get_dimensions(), (LCD_WIDTH, LCD_HEIGHT));
+        assert_eq!(driver.dsi().command_count(), 0);
+        assert_eq!(driver.layer_config(0).unwrap().window_x1, LCD_WIDTH);
+
+        // The raw entry points reject values outside the enums
+        assert_eq!(Orientation::from_u32(7), None);
+        assert_eq!(driver.init(0x12, OTM8009A_ORIENTATION_LANDSCAPE), Err(Otm8009aError::InvalidConfig));
+        assert_eq!(driver.get_dimensions(), (LCD_WIDTH, LCD_HEIGHT));
+    }
+
+    #[cfg(feature = "nt35510")]
+    #[test]
+    fn test_nt35510_init_with() {
+        use crate::nt35510::{defs::NT35510_ID, NT35510Driver, Nt35510Error};
+
+        let dsi = MockDsiInterface::with_id(0x00, NT35510_ID, 0x00);
+        let mut driver: NT35510Driver<_, _, MockFramebuffer> = new_panel_driver(dsi, Orientation::Portrait, None);
+        let rgb666 = DisplayConfig::new(Orientation::Portrait, ColorFormat::Rgb666);
+        assert_eq!(driver.init_with(rgb666), Err(Nt35510Error::InvalidConfig));
+
+        driver.init_with(presets::STANDARD_PORTRAIT).unwrap();
+        assert_eq!(driver.get_dimensions(), (LCD_HEIGHT, LCD_WIDTH));
+    }
+}
@@ -0,0 +1,42 @@
+//! Tests for the MIPI DBI drawing helpers
+//!
+//! Only built with the `dbi` feature.
+
+#[cfg(all(test, feature = "dbi"))]
+mod tests {
+    use crate::dbi::*;
+    use crate::dcs::*;
+    use crate::error::{BusError, DbiError};
+    use crate::testing::mocks::*;
+    use crate::traits::pixel::Rgb565;
+
+    #[test]
+    fn test_pixel_encoding() {
+        let color = Rgb565::from_rgb(0xFF, 0x00, 0xFF);
+
+        assert_eq!(EncodedPixel::new(color, ColorFormat::Rgb565).as_bytes(), &[0xF8, 0x1F]);
+        assert_eq!(EncodedPixel::new(color, ColorFormat::Rgb666).as_bytes(), &[0xFF, 0x00, 0xFF]);
+    }
+
+    #[test]
+    fn test_fill_window_streams_pixels() {
+        let mut bus = MockDisplayBus::new();
+
+        fill_window(&mut bus, 10, 300, 20, 2, Rgb565(0x07E0), ColorFormat::Rgb565).unwrap();
+
+        assert_eq!(bus.writes, vec![
+            BusWrite::Command(SET_COLUMN_ADDRESS),
+            BusWrite::Data(vec![0x00, 10, 0x00, 29]),
+            BusWrite::Command(SET_PAGE_ADDRESS),
+            BusWrite::Data(vec![0x01, 0x2C, 0x01, 0x2D]),
+            BusWrite::Command(WRITE_MEMORY_START),
+            BusWrite::Repeated(vec![0x07, 0xE0], 40),
+        ]);
+    }
+
+    #[test]
+    fn test_bus_error_message() {
+        let error = DbiError::Bus(BusError::command(WRITE_MEMORY_START, MockBusError::SimulatedFailure));
+        assert_eq!(error.to_string(), "DBI error SimulatedFailure on command 0x2C");
+    }
+}
@@ -0,0 +1,346 @@
+//! Tests for the OTM8009A driver
+//!
+//! Drives `OTM8009ADriver` end to end against the mock hardware interfaces.
+
+#[cfg(test)]
+mod tests {
+    use crate::error::DsiError;
+    use crate::otm8009a::defs::*;
+    use crate::otm8009a::driver::OTM8009ADriver;
+    use crate::testing::mocks::*;
+    use crate::testing::traits::FramebufferTestingExt;
+    use crate::traits::{pixel::{Argb8888, Rgb565}, *};
+
+    #[test]
+    fn test_init_marks_driver_ready() {
+        let mut driver = new_driver();
+        assert!(!driver.is_initialized());
+
+        driver.init(OTM8009A_FORMAT_RGB565, OTM8009A_ORIENTATION_LANDSCAPE).unwrap();
+
+        assert!(driver.is_initialized());
+        assert_eq!(driver.get_dimensions(), (LCD_WIDTH, LCD_HEIGHT));
+    }
+
+    #[test]
+    fn test_drawing_requires_init() {
+        let mut driver = new_driver();
+
+        assert_eq!(driver.set_pixel(0, 0, Rgb565(0xFFFF)), Err(Otm8009aError::NotReady));
+        assert_eq!(driver.fill_rect(0, 0, 1, 1, Rgb565(0xFFFF)), Err(Otm8009aError::NotReady));
+        assert_eq!(driver.clear(Rgb565(0xFFFF)), Err(Otm8009aError::NotReady));
+        assert_eq!(driver.update(), Err(Otm8009aError::NotReady));
+    }
+
+    #[test]
+    fn test_drawing_reaches_framebuffer() {
+        let mut driver = new_driver();
+        driver.init(OTM8009A_FORMAT_RGB565, OTM8009A_ORIENTATION_LANDSCAPE).unwrap();
+
+        driver.fill_rect(10, 10, 4, 4, Rgb565(0xF800)).unwrap();
+        driver.set_pixel(100, 200, Rgb565(0x07E0)).unwrap();
+
+        let framebuffer = driver.framebuffer();
+        assert_eq!(framebuffer.get_pixel(10, 10), Some(Rgb565(0xF800)));
+        assert_eq!(framebuffer.get_pixel(13, 13), Some(Rgb565(0xF800)));
+        assert_eq!(framebuffer.get_pixel(14, 14), Some(Rgb565(0x0000)));
+        assert_eq!(framebuffer.get_pixel(100, 200), Some(Rgb565(0x07E0)));
+    }
+
+    #[test]
+    fn test_update_relatches_framebuffer() {
+        let mut driver = new_driver();
+        driver.init(OTM8009A_FORMAT_RGB565, OTM8009A_ORIENTATION_LANDSCAPE).unwrap();
+
+        driver.update().unwrap();
+
+        let address = driver.framebuffer().get_buffer_ptr() as u32;
+        assert_eq!(driver.ltdc().framebuffer_addresses, vec![(0, address)]);
+    }
+
+    #[test]
+    fn test_layer_follows_pixel_type() {
+        let mut driver = new_driver();
+        driver.init(OTM8009A_FORMAT_RGB565, OTM8009A_ORIENTATION_LANDSCAPE).unwrap();
+
+        let config = driver.ltdc().get_layer_config(0).unwrap();
+        assert_eq!(config.pixel_format, PixelFormat::Rgb565);
+        assert_eq!(config.framebuffer_pitch, LCD_WIDTH * 2);
+
+        let mut driver = OTM8009ADriver::new(
+            MockDsiInterface::new(),
+            MockLtdcInterface::new(),
+            MockFramebuffer::<Argb8888>::new(LCD_WIDTH, LCD_HEIGHT),
+        );
+        driver.init(OTM8009A_FORMAT_RGB888, OTM8009A_ORIENTATION_LANDSCAPE).unwrap();
+
+        let config = driver.ltdc().get_layer_config(0).unwrap();
+        assert_eq!(config.pixel_format, PixelFormat::Argb8888);
+        assert_eq!(config.framebuffer_pitch, LCD_WIDTH * 4);
+        assert_eq!(driver.framebuffer().get_buffer_size(), LCD_WIDTH as usize * LCD_HEIGHT as usize * 4);
+    }
+
+    #[test]
+    fn test_init_checks_panel_id() {
+        let mut driver = new_driver();
+        driver.init(OTM8009A_FORMAT_RGB565, OTM8009A_ORIENTATION_LANDSCAPE).unwrap();
+
+        assert_eq!(&driver.dsi().reads[..3], &[commands::READ_ID1, commands::READ_ID2, commands::READ_ID3]);
+
+        let mut dsi = MockDsiInterface::new();
+        dsi.set_register(commands::READ_ID1, &[0x80]);
+        let mut driver: MockDriver = new_panel_driver(dsi, Orientation::Landscape, None);
+
+        assert_eq!(
+            driver.init(OTM8009A_FORMAT_RGB565, OTM8009A_ORIENTATION_LANDSCAPE),
+            Err(Otm8009aError::WrongPanelId(0x80))
+        );
+        assert!(!driver.is_initialized());
+        assert_eq!(driver.dsi().command_count(), 0);
+    }
+
+    #[test]
+    fn test_read_status_registers() {
+        let mut dsi = MockDsiInterface::new();
+        dsi.set_register(commands::READ_ID2, &[0x12]);
+        dsi.set_register(commands::READ_ID3, &[0x34]);
+        dsi.set_register(commands::GET_POWER_MODE, &[0x9C]);
+        dsi.set_register(commands::GET_ADDRESS_MODE, &[0x60]);
+        dsi.set_register(commands::GET_PIXEL_FORMAT, &[0x55]);
+        let mut driver: MockDriver = new_panel_driver(dsi, Orientation::Landscape, None);
+
+        assert_eq!(
+            driver.read_id(),
+            Ok(PanelId { manufacturer: OTM8009A_ID, version: 0x12, driver: 0x34 })
+        );
+        assert_eq!(driver.read_power_mode(), Ok(0x9C));
+        assert_eq!(driver.read_madctl(), Ok(0x60));
+        assert_eq!(driver.read_pixel_format(), Ok(0x55));
+        assert_eq!(driver.dsi().max_return_packet_size, 1);
+    }
+
+    #[test]
+    fn test_read_failure_keeps_dsi_error() {
+        let mut driver = new_driver();
+        driver.dsi_mut().set_should_fail(true);
+
+        let error = DsiError::command(commands::GET_POWER_MODE, MockDsiError::SimulatedFailure);
+        assert_eq!(driver.read_power_mode(), Err(Otm8009aError::Dsi(error)));
+    }
+
+    #[test]
+    fn test_init_sends_real_opcodes() {
+        let mut driver = new_driver();
+        driver.init(OTM8009A_FORMAT_RGB565, OTM8009A_ORIENTATION_LANDSCAPE).unwrap();
+
+        let sent: Vec<&[u8]> = driver.dsi().commands_sent.iter()
+            .map(|command| command.params.as_slice())
+            .collect();
+        assert!(sent.iter().all(|payload| !payload.is_empty()));
+        assert!(driver.dsi().commands_sent.iter().all(|command| command.nb_params + 1 == command.params.len()));
+
+        // Enter command 2, then ORISE command 2
+        assert_eq!(&sent[..4], &[
+            &[0x00, 0x00][..],
+            &[0xFF, 0x80, 0x09, 0x01][..],
+            &[0x00, 0x80][..],
+            &[0xFF, 0x80, 0x09][..],
+        ]);
+
+        let position = |payload: &[u8]| sent.iter().position(|sent| *sent == payload).unwrap();
+        let sleep_out = position(&[commands::SLEEP_OUT]);
+        assert!(position(&init_sequences::CMD_EXIT_CMD2) < sleep_out);
+        assert!(sleep_out < position(&init_sequences::CMD_RGB565));
+        assert!(position(&init_sequences::CMD_RGB565) < position(&init_sequences::CMD_LANDSCAPE));
+        assert!(position(&init_sequences::CMD_LANDSCAPE) < position(&[commands::DISPLAY_ON]));
+        assert_eq!(sent.last(), Some(&&[commands::WRITE_MEMORY_START][..]));
+        assert!(driver.dsi().delays_requested.contains(&timing::SLEEP_OUT_DELAY_MS));
+    }
+
+    /// Bring up any panel and draw to it through the trait alone
+    fn bring_up<P: DisplayPanel>(panel: &mut P, config: DisplayConfig, color: P::Pixel) -> Result<(u16, u16), P::Error> {
+        panel.init(config)?;
+        panel.set_brightness(0x80)?;
+        panel.clear(color)?;
+        panel.flush()?;
+        Ok(panel.get_dimensions())
+    }
+
+    #[test]
+    fn test_driver_as_display_panel() {
+        let mut driver = new_driver();
+
+        let color = Rgb565(0x07E0);
+        assert_eq!(bring_up(&mut driver, presets::STANDARD_LANDSCAPE, color), Ok((LCD_WIDTH, LCD_HEIGHT)));
+        assert!(DisplayPanel::is_initialized(&driver));
+        assert_eq!(DisplayPanel::get_pixel_format(&driver), PixelFormat::Rgb565);
+        assert!(driver.framebuffer().verify_region(0, 0, LCD_WIDTH, LCD_HEIGHT, color));
+
+        DisplayPanel::set_orientation(&mut driver, Orientation::LandscapeFlipped).unwrap();
+        assert!(driver.dsi().commands_sent.iter().any(|command| command.params == init_sequences::CMD_LANDSCAPE_FLIPPED));
+
+        driver.dsi_mut().clear_history();
+        DisplayPanel::enter_sleep(&mut driver).unwrap();
+        DisplayPanel::exit_sleep(&mut driver).unwrap();
+        DisplayPanel::set_power_mode(&mut driver, PowerMode::IdleMode).unwrap();
+        assert_eq!(DisplayPanel::power_mode(&driver), PowerMode::IdleMode);
+        let opcodes: Vec<u8> = driver.dsi().commands_sent.iter().map(|command| command.params[0]).collect();
+        assert_eq!(opcodes, vec![0x28, 0x10, 0x11, 0x29, 0x39]);
+    }
+
+    #[test]
+    fn test_tearing_effect_commands() {
+        let mut driver = new_driver();
+        assert_eq!(driver.enable_tearing_effect(TearMode::VBlank), Err(Otm8009aError::NotReady));
+
+        driver.init(OTM8009A_FORMAT_RGB565, OTM8009A_ORIENTATION_LANDSCAPE).unwrap();
+        driver.dsi_mut().clear_history();
+
+        driver.enable_tearing_effect(TearMode::VHBlank).unwrap();
+        driver.set_tear_scanline(0x123).unwrap();
+        assert_eq!(driver.tear_mode(), Some(TearMode::VHBlank));
+        driver.disable_tearing_effect().unwrap();
+        assert_eq!(driver.tear_mode(), None);
+
+        let sent: Vec<&[u8]> = driver.dsi().commands_sent.iter()
+            .map(|command| command.params.as_slice())
+            .collect();
+        assert_eq!(sent, vec![
+            &[commands::SET_TEAR_ON, 0x01][..],
+            &[commands::SET_TEAR_SCANLINE, 0x01, 0x23][..],
+            &[commands::SET_TEAR_OFF][..],
+        ]);
+    }
+
+    #[test]
+    fn test_video_mode_present_waits_for_vblank() {
+        let mut driver = new_driver();
+        assert_eq!(driver.present(), Err(Otm8009aError::NotReady));
+
+        driver.init(OTM8009A_FORMAT_RGB565, OTM8009A_ORIENTATION_LANDSCAPE).unwrap();
+        driver.dsi_mut().clear_history();
+        driver.present().unwrap();
+
+        assert_eq!(driver.ltdc().vblank_waits, 1);
+        assert_eq!(driver.ltdc().framebuffer_addresses.len(), 1);
+        assert_eq!(driver.dsi().command_count(), 0);
+    }
+
+    #[test]
+    fn test_command_mode_present_waits_for_tearing_effect() {
+        let mut driver = new_driver();
+        driver.init(OTM8009A_FORMAT_RGB565, OTM8009A_ORIENTATION_LANDSCAPE).unwrap();
+        driver.set_refresh_mode(RefreshMode::Command);
+
+        // Without a TE signal there is nothing to synchronize with
+        assert_eq!(driver.present_region(0, 0, 2, 2), Err(Otm8009aError::InvalidConfig));
+
+        driver.enable_tearing_effect(TearMode::VBlank).unwrap();
+        driver.dsi_mut().clear_history();
+        driver.present_region(0, 0, 2, 2).unwrap();
+
+        assert_eq!(driver.dsi().tearing_effect_waits, vec![0]);
+        assert_eq!(driver.dsi().commands_sent[0].params[0], commands::SET_COLUMN_ADDRESS);
+        assert_eq!(driver.ltdc().vblank_waits, 0);
+    }
+
+    #[test]
+    fn test_partial_and_idle_modes() {
+        let mut driver = new_driver();
+        assert_eq!(driver.power_mode(), PowerMode::Off);
+        assert_eq!(driver.enter_idle_mode(), Err(Otm8009aError::NotReady));
+
+        driver.init(OTM8009A_FORMAT_RGB565, OTM8009A_ORIENTATION_LANDSCAPE).unwrap();
+        assert_eq!(driver.power_mode(), PowerMode::Normal);
+        assert_eq!(driver.enter_partial_mode(0, LCD_HEIGHT), Err(Otm8009aError::InvalidConfig));
+        driver.dsi_mut().clear_history();
+
+        driver.enter_partial_mode(0x10, 0x13F).unwrap();
+        assert_eq!(driver.power_mode(), PowerMode::PartialDisplay);
+        driver.enter_idle_mode().unwrap();
+        assert_eq!(driver.power_mode(), PowerMode::IdleMode);
+        driver.enter_normal_mode().unwrap();
+        assert_eq!(driver.power_mode(), PowerMode::Normal);
+
+        let sent: Vec<&[u8]> = driver.dsi().commands_sent.iter()
+            .map(|command| command.params.as_slice())
+            .collect();
+        assert_eq!(sent, vec![
+            &[commands::SET_PARTIAL_AREA, 0x00, 0x10, 0x01, 0x3F][..],
+            &[commands::PARTIAL_MODE_ON][..],
+            &[commands::NORMAL_MODE_ON][..],
+            &[commands::IDLE_MODE_ON][..],
+            &[commands::IDLE_MODE_OFF][..],
+        ]);
+    }
+
+    #[test]
+    fn test_sleep_leaves_partial_mode() {
+        let mut driver = new_driver();
+        driver.init(OTM8009A_FORMAT_RGB565, OTM8009A_ORIENTATION_LANDSCAPE).unwrap();
+        driver.enter_partial_mode(0, 99).unwrap();
+        driver.dsi_mut().clear_history();
+
+        driver.enter_sleep().unwrap();
+        assert_eq!(driver.power_mode(), PowerMode::Sleep);
+        assert_eq!(
+            driver.enter_partial_mode(0, 99),
+            Err(Otm8009aError::IllegalTransition { from: PowerMode::Sleep, to: PowerMode::PartialDisplay })
+        );
+        driver.exit_sleep().unwrap();
+        assert_eq!(driver.power_mode(), PowerMode::Normal);
+
+        let opcodes: Vec<u8> = driver.dsi().commands_sent.iter().map(|command| command.params[0]).collect();
+        assert_eq!(opcodes, vec![commands::NORMAL_MODE_ON, 0x28, 0x10, 0x11, 0x29]);
+    }
+
+    #[test]
+    fn test_brightness_and_cabc_registers() {
+        let mut driver = new_driver();
+        assert_eq!(driver.set_cabc_mode(CabcMode::Ui), Err(Otm8009aError::NotReady));
+
+        driver.init(OTM8009A_FORMAT_RGB565, OTM8009A_ORIENTATION_LANDSCAPE).unwrap();
+        assert_eq!(driver.brightness(), 0x7F);
+        driver.dsi_mut().clear_history();
+
+        driver.set_brightness(0x40).unwrap();
+        driver.set_ctrl_display(CtrlDisplay { dimming: false, ..Default::default() }).unwrap();
+        driver.set_cabc_mode(CabcMode::Moving).unwrap();
+        driver.set_cabc_min_brightness(0x10).unwrap();
+        driver.disable_cabc().unwrap();
+
+        assert_eq!(driver.brightness(), 0x40);
+        let sent: Vec<&[u8]> = driver.dsi().commands_sent.iter()
+            .map(|command| command.params.as_slice())
+            .collect();
+        assert_eq!(sent, vec![
+            &[commands::WRITE_DISPLAY_BRIGHTNESS, 0x40][..],
+            &[commands::WRITE_CTRL_DISPLAY, 0x24][..],
+            &[commands::WRITE_CABC, 0x03][..],
+            &[commands::WRITE_CABC_MIN_BRIGHTNESS, 0x10][..],
+            &[commands::WRITE_CABC, 0x00][..],
+        ]);
+        assert_eq!(CtrlDisplay::default().bits(), init_sequences::CMD_BRIGHTNESS_CTRL[1]);
+    }
+
+    #[test]
+    fn test_fade_brightness() {
+        let mut driver = new_driver();
+        driver.init(OTM8009A_FORMAT_RGB565, OTM8009A_ORIENTATION_LANDSCAPE).unwrap();
+        driver.set_brightness(0).unwrap();
+        driver.dsi_mut().clear_history();
+
+        driver.fade_brightness(200, 50).unwrap();
+
+        let levels: Vec<u8> = driver.dsi().commands_sent.iter().map(|command| command.params[1]).collect();
+        assert_eq!(levels, vec![40, 80, 120, 160, 200]);
+        assert_eq!(driver.dsi().delays_requested, vec![10; 4]);
+        assert_eq!(driver.brightness(), 200);
+
+        // Never more steps than levels to cross
+        driver.dsi_mut().clear_history();
+        driver.fade_brightness(198, 1000).unwrap();
+        assert_eq!(driver.dsi().command_count(), 2);
+        assert_eq!(driver.dsi().delays_requested, vec![500]);
+    }
+}
@@ -0,0 +1,99 @@
+//! Tests for the bus errors kept by the DSI and DBI panel drivers
+
+#[cfg(test)]
+mod tests {
+    use crate::error::{DsiError, InitStage};
+    use crate::otm8009a::defs::*;
+    use crate::otm8009a::driver::OTM8009ADriver;
+    use crate::script::InitStep;
+    use crate::testing::mocks::*;
+
+    type MockDriver = OTM8009ADriver<MockDsiInterface, MockLtdcInterface, MockFramebuffer>;
+
+    fn failing_on(opcode: u8) -> MockDriver {
+        let mut dsi = MockDsiInterface::new();
+        dsi.fail_on = Some(opcode);
+        OTM8009ADriver::new(dsi, MockLtdcInterface::new(), MockFramebuffer::new(LCD_WIDTH, LCD_HEIGHT))
+    }
+
+    fn init(driver: &mut MockDriver) -> Result<(), Otm8009aError<MockDsiError, MockLtdcError>> {
+        driver.init(OTM8009A_FORMAT_RGB565, OTM8009A_ORIENTATION_LANDSCAPE)
+    }
+
+    fn failure(opcode: u8, stage: InitStage) -> Otm8009aError<MockDsiError, MockLtdcError> {
+        Otm8009aError::Dsi(DsiError::command(opcode, MockDsiError::SimulatedFailure).during(stage))
+    }
+
+    #[test]
+    fn test_script_step_is_recorded() {
+        let sleep_out = scripts::POWER_UP.iter()
+            .position(|step| *step == InitStep::Write(&single_commands::SLEEP_OUT))
+            .unwrap() as u16;
+        let mut driver = failing_on(commands::SLEEP_OUT);
+        assert_eq!(init(&mut driver), Err(failure(commands::SLEEP_OUT, InitStage::PowerUp(sleep_out))));
+        assert!(!driver.is_initialized());
+
+        let mut driver = failing_on(commands::DISPLAY_ON);
+        assert_eq!(init(&mut driver), Err(failure(commands::DISPLAY_ON, InitStage::DisplayOn(4))));
+    }
+
+    #[test]
+    fn test_init_stage_is_recorded() {
+        let mut driver = failing_on(init_sequences::CMD_RGB565[0]);
+        assert_eq!(init(&mut driver), Err(failure(0x3A, InitStage::PixelFormat)));
+
+        let mut driver = failing_on(init_sequences::CMD_LANDSCAPE[0]);
+        assert_eq!(init(&mut driver), Err(failure(0x36, InitStage::Orientation)));
+
+        // The same write outside init has no stage
+        let mut driver = OTM8009ADriver::new(
+            MockDsiInterface::new(),
+            MockLtdcInterface::new(),
+            MockFramebuffer::new(LCD_WIDTH, LCD_HEIGHT),
+        );
+        init(&mut driver).unwrap();
+        driver.dsi_mut().fail_on = Some(0x36);
+        let error = DsiError::command(0x36, MockDsiError::SimulatedFailure);
+        assert_eq!(driver.set_orientation(Orientation::LandscapeFlipped), Err(Otm8009aError::Dsi(error)));
+    }
+
+    #[test]
+    fn test_ltdc_error_is_kept() {
+        let mut ltdc = MockLtdcInterface::new();
+        ltdc.set_should_fail(true);
+        let mut driver = OTM8009ADriver::new(MockDsiInterface::new(), ltdc, MockFramebuffer::new(LCD_WIDTH, LCD_HEIGHT));
+
+        assert_eq!(init(&mut driver), Err(Otm8009aError::Ltdc(MockLtdcError::SimulatedFailure)));
+    }
+
+    #[test]
+    fn test_host_errors_have_no_opcode() {
+        let mut dsi = MockDsiInterface::new();
+        dsi.set_should_fail(true);
+        let mut driver: MockDriver = OTM8009ADriver::new(dsi, MockLtdcInterface::new(), MockFramebuffer::new(LCD_WIDTH, LCD_HEIGHT));
+
+        let error = DsiError::host(MockDsiError::SimulatedFailure);
+        assert_eq!(driver.reset(), Err(Otm8009aError::Dsi(error)));
+    }
+
+    #[test]
+    fn test_stage_names() {
+        assert_eq!(InitStage::PowerUp(3).name(), "power_up");
+        assert_eq!(InitStage::PowerUp(3).step(), Some(3));
+        assert_eq!(InitStage::ReadId.step(), None);
+
+        // The innermost stage wins
+        let error = DsiError::command(0x11, ()).during(InitStage::PowerUp(2)).during(InitStage::PixelFormat);
+        assert_eq!(error.context.stage, Some(InitStage::PowerUp(2)));
+    }
+
+    #[test]
+    fn test_display_messages() {
+        assert_eq!(
+            failure(0x11, InitStage::PowerUp(44)).to_string(),
+            "DSI error SimulatedFailure on command 0x11 during power_up step 44"
+        );
+        let error: Otm8009aError<MockDsiError, MockLtdcError> = Otm8009aError::Ltdc(MockLtdcError::InvalidLayer);
+        assert_eq!(error.to_string(), "LTDC error InvalidLayer");
+    }
+}
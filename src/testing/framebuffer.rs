@@ -0,0 +1,97 @@
+//! Tests for the slice-backed framebuffer
+
+#[cfg(test)]
+mod tests {
+    use crate::framebuffer::SliceFramebuffer;
+    use crate::otm8009a::defs::*;
+    use crate::otm8009a::driver::OTM8009ADriver;
+    use crate::testing::mocks::*;
+    use crate::traits::{pixel::{Argb8888, Rgb565}, *};
+
+    fn leak<P: PixelType>(len: usize) -> &'static mut [P] {
+        Box::leak(vec![P::default(); len].into_boxed_slice())
+    }
+
+    #[test]
+    fn test_rejects_short_buffers() {
+        assert!(SliceFramebuffer::<Rgb565>::new(leak(15), 4, 4).is_none());
+        assert!(SliceFramebuffer::<Rgb565>::with_stride(leak(64), 8, 4, 6).is_none());
+        assert!(SliceFramebuffer::<Rgb565>::with_stride(leak(31), 4, 4, 8).is_none());
+        assert!(SliceFramebuffer::<Rgb565>::with_stride(leak(32), 4, 4, 8).is_some());
+
+        // A stride so large that stride * height wraps around
+        let stride = usize::MAX / 2 + 1;
+        assert!(SliceFramebuffer::<Rgb565>::with_stride(leak(32), 4, 2, stride).is_none());
+    }
+
+    #[test]
+    fn test_stride_leaves_padding_untouched() {
+        let mut framebuffer = SliceFramebuffer::with_stride(leak(24), 4, 3, 8).unwrap();
+
+        framebuffer.clear(Rgb565(0xFFFF));
+        framebuffer.fill_rect(1, 1, 2, 2, Rgb565(0xF800));
+        framebuffer.set_pixel(3, 0, Rgb565(0x001F));
+
+        assert_eq!(framebuffer.row(0).unwrap(), &[Rgb565(0xFFFF), Rgb565(0xFFFF), Rgb565(0xFFFF), Rgb565(0x001F)]);
+        assert_eq!(framebuffer.row(1).unwrap(), &[Rgb565(0xFFFF), Rgb565(0xF800), Rgb565(0xF800), Rgb565(0xFFFF)]);
+        assert_eq!(framebuffer.get_pixel(2, 2), Some(Rgb565(0xF800)));
+
+        let memory = framebuffer.into_inner();
+        assert!(memory[4..8].iter().all(|&pixel| pixel == Rgb565(0x0000)));
+        assert!(memory[12..16].iter().all(|&pixel| pixel == Rgb565(0x0000)));
+    }
+
+    #[test]
+    fn test_out_of_bounds_drawing_is_clipped() {
+        let mut framebuffer = SliceFramebuffer::new(leak(16), 4, 4).unwrap();
+
+        framebuffer.set_pixel(4, 0, Rgb565(0xFFFF));
+        framebuffer.fill_rect(4, 4, 2, 2, Rgb565(0xFFFF));
+        framebuffer.fill_rect(2, 3, u16::MAX, u16::MAX, Rgb565(0xF800));
+
+        assert_eq!(framebuffer.get_pixel(4, 0), None);
+        assert_eq!(framebuffer.row(2).unwrap(), &[Rgb565(0x0000); 4]);
+        assert_eq!(framebuffer.row(3).unwrap(), &[Rgb565(0x0000), Rgb565(0x0000), Rgb565(0xF800), Rgb565(0xF800)]);
+    }
+
+    #[test]
+    fn test_reports_real_memory() {
+        let framebuffer = SliceFramebuffer::<Argb8888>::with_stride(leak(40), 4, 4, 10).unwrap();
+
+        assert_eq!(framebuffer.get_pitch(), 40);
+        assert_eq!(framebuffer.get_buffer_size(), 160);
+
+        let raw: &'static mut [u16] = Box::leak(vec![0u16; 16].into_boxed_slice());
+        let address = raw.as_ptr();
+        let framebuffer = SliceFramebuffer::from_u16(raw, 4, 4, 4).unwrap();
+        assert_eq!(framebuffer.get_buffer_ptr() as *const u16, address);
+        assert_eq!(framebuffer.get_buffer_size(), 32);
+    }
+
+    #[test]
+    fn test_driver_scans_out_stride() {
+        let stride = LCD_WIDTH as usize + 32;
+        let framebuffer = SliceFramebuffer::<Rgb565>::with_stride(
+            leak(stride * LCD_HEIGHT as usize), LCD_WIDTH, LCD_HEIGHT, stride,
+        ).unwrap();
+        let mut driver = OTM8009ADriver::new(MockDsiInterface::new(), MockLtdcInterface::new(), framebuffer);
+
+        driver.init(OTM8009A_FORMAT_RGB565, OTM8009A_ORIENTATION_LANDSCAPE).unwrap();
+
+        let config = driver.ltdc().get_layer_config(0).unwrap();
+        assert_eq!(config.framebuffer_pitch as usize, stride * 2);
+        assert_eq!(config.framebuffer_address, driver.framebuffer().get_buffer_ptr() as u32);
+    }
+
+    #[test]
+    fn test_write_row_is_clipped() {
+        let mut framebuffer = SliceFramebuffer::with_stride(leak(16), 3, 2, 8).unwrap();
+
+        framebuffer.write_row(1, 1, &[Rgb565(1), Rgb565(2), Rgb565(3)]);
+        framebuffer.write_row(3, 0, &[Rgb565(4)]);
+
+        assert_eq!(framebuffer.row(0).unwrap(), &[Rgb565(0), Rgb565(0), Rgb565(0)]);
+        assert_eq!(framebuffer.row(1).unwrap(), &[Rgb565(0), Rgb565(1), Rgb565(2)]);
+        assert_eq!(framebuffer.into_inner()[11], Rgb565(0));
+    }
+}
@@ -0,0 +1,110 @@
+//! Tests for command-mode GRAM writes
+
+#[cfg(test)]
+mod tests {
+    use crate::gram::*;
+    use crate::otm8009a::defs::*;
+    use crate::otm8009a::driver::OTM8009ADriver;
+    use crate::testing::mocks::*;
+    use crate::traits::{pixel::{Argb8888, Rgb565, Rgb888}, *};
+
+    fn sent(dsi: &MockDsiInterface) -> Vec<Vec<u8>> {
+        dsi.commands_sent.iter().map(|command| command.params.clone()).collect()
+    }
+
+    #[test]
+    fn test_region_is_streamed_in_whole_pixels() {
+        let mut dsi = MockDsiInterface::new();
+        let mut framebuffer = MockFramebuffer::new(300, 4);
+        framebuffer.fill_rect(0, 1, 300, 2, Rgb565(0xF800));
+
+        write_region(&mut dsi, &framebuffer, ColorFormat::Rgb565, 0, 1, 200, 2).unwrap();
+
+        let sent = sent(&dsi);
+        assert_eq!(sent[0], vec![commands::SET_COLUMN_ADDRESS, 0x00, 0x00, 0x00, 199]);
+        assert_eq!(sent[1], vec![commands::SET_PAGE_ADDRESS, 0x00, 0x01, 0x00, 0x02]);
+
+        let packets = &sent[2..];
+        assert_eq!(packets[0][0], commands::WRITE_MEMORY_START);
+        assert!(packets[1..].iter().all(|packet| packet[0] == commands::WRITE_MEMORY_CONTINUE));
+        assert!(packets.iter().all(|packet| packet.len() <= MAX_LONG_WRITE && packet.len() % 2 == 1));
+
+        let pixels: Vec<u8> = packets.iter().flat_map(|packet| packet[1..].to_vec()).collect();
+        assert_eq!(pixels.len(), 200 * 2 * 2);
+        assert!(pixels.chunks(2).all(|pixel| pixel == [0xF8, 0x00]));
+    }
+
+    #[test]
+    fn test_region_is_clipped_to_framebuffer() {
+        let mut dsi = MockDsiInterface::new();
+        let framebuffer = MockFramebuffer::<Rgb888>::new(4, 4);
+
+        write_region(&mut dsi, &framebuffer, ColorFormat::Rgb888, 2, 3, 10, 10).unwrap();
+
+        let sent = sent(&dsi);
+        assert_eq!(sent[0], vec![commands::SET_COLUMN_ADDRESS, 0x00, 0x02, 0x00, 0x03]);
+        assert_eq!(sent[1], vec![commands::SET_PAGE_ADDRESS, 0x00, 0x03, 0x00, 0x03]);
+        assert_eq!(sent[2].len(), 1 + 2 * 3);
+
+        dsi.clear_history();
+        write_region(&mut dsi, &framebuffer, ColorFormat::Rgb888, 4, 0, 1, 1).unwrap();
+        assert!(dsi.commands_sent.is_empty());
+    }
+
+    #[test]
+    fn test_pixel_size_must_match_color_format() {
+        let mut dsi = MockDsiInterface::new();
+        let framebuffer = MockFramebuffer::<Rgb565>::new(4, 4);
+
+        assert_eq!(write_region(&mut dsi, &framebuffer, ColorFormat::Rgb888, 0, 0, 4, 4), Err(GramError::InvalidConfig));
+        assert_eq!(write_region(&mut dsi, &framebuffer, ColorFormat::Rgb666, 0, 0, 4, 4), Err(GramError::InvalidConfig));
+        assert!(dsi.commands_sent.is_empty());
+
+        // 18-bit pixels take three bytes like 24-bit ones
+        let framebuffer = MockFramebuffer::<Rgb888>::new(4, 4);
+        write_region(&mut dsi, &framebuffer, ColorFormat::Rgb666, 0, 0, 1, 1).unwrap();
+        assert_eq!(dsi.get_last_command().unwrap().params.len(), 1 + 3);
+
+        // The driver checks against the COLMOD it sent
+        let mut driver = OTM8009ADriver::new(
+            MockDsiInterface::new(),
+            MockLtdcInterface::new(),
+            MockFramebuffer::<Rgb565>::new(LCD_WIDTH, LCD_HEIGHT),
+        );
+        driver.init(OTM8009A_FORMAT_RGB888, OTM8009A_ORIENTATION_LANDSCAPE).unwrap();
+        assert_eq!(driver.flush_region(0, 0, 1, 1), Err(Otm8009aError::InvalidConfig));
+    }
+
+    #[test]
+    fn test_dcs_pixel_byte_order() {
+        let mut out = [0u8; 3];
+
+        Rgb888::from_rgb(0x12, 0x34, 0x56).write_dcs_bytes(&mut out);
+        assert_eq!(out, [0x12, 0x34, 0x56]);
+
+        Argb8888::from_argb(0xFF, 0xAB, 0xCD, 0xEF).write_dcs_bytes(&mut out);
+        assert_eq!(out, [0xAB, 0xCD, 0xEF]);
+
+        Rgb565(0x1234).write_dcs_bytes(&mut out);
+        assert_eq!(out[..2], [0x12, 0x34]);
+    }
+
+    #[test]
+    fn test_driver_flush_region() {
+        let mut driver = OTM8009ADriver::new(
+            MockDsiInterface::new(),
+            MockLtdcInterface::new(),
+            MockFramebuffer::new(LCD_WIDTH, LCD_HEIGHT),
+        );
+        assert_eq!(driver.flush_region(0, 0, 1, 1), Err(Otm8009aError::NotReady));
+
+        driver.init(OTM8009A_FORMAT_RGB565, OTM8009A_ORIENTATION_LANDSCAPE).unwrap();
+        assert_eq!(driver.flush_region(LCD_WIDTH, 0, 1, 1), Err(Otm8009aError::InvalidCoordinates));
+
+        driver.set_pixel(5, 6, Rgb565(0x07E0)).unwrap();
+        driver.dsi_mut().clear_history();
+        driver.flush_region(5, 6, 1, 1).unwrap();
+
+        assert_eq!(driver.dsi().get_last_command().unwrap().params, vec![commands::WRITE_MEMORY_START, 0x07, 0xE0]);
+    }
+}
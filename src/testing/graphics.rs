@@ -0,0 +1,96 @@
+//! Tests for the embedded-graphics integration
+//!
+//! Only built with `cargo test --features embedded-graphics`.
+
+#[cfg(all(test, feature = "embedded-graphics"))]
+mod tests {
+    use embedded_graphics_core::{
+        draw_target::DrawTarget,
+        geometry::{Point, Size},
+        pixelcolor::{Rgb565, RgbColor},
+        primitives::Rectangle,
+        Pixel,
+    };
+
+    use crate::graphics::FramebufferTarget;
+    use crate::otm8009a::defs::*;
+    use crate::otm8009a::driver::OTM8009ADriver;
+    use crate::testing::mocks::*;
+    use crate::testing::traits::FramebufferTestingExt;
+    use crate::traits::pixel;
+
+    #[test]
+    fn test_draw_iter_skips_offscreen_pixels() {
+        let mut target = FramebufferTarget::new(MockFramebuffer::new(4, 4));
+
+        target.draw_iter([
+            Pixel(Point::new(1, 2), Rgb565::RED),
+            Pixel(Point::new(-1, 0), Rgb565::RED),
+            Pixel(Point::new(4, 0), Rgb565::RED),
+        ]).unwrap();
+
+        let framebuffer = target.framebuffer();
+        assert_eq!(framebuffer.get_pixel(1, 2), Some(pixel::Rgb565(0xF800)));
+        assert!(framebuffer.verify_region(0, 0, 4, 2, pixel::Rgb565(0x0000)));
+    }
+
+    #[test]
+    fn test_fill_solid_is_clipped() {
+        let mut target = FramebufferTarget::new(MockFramebuffer::new(8, 8));
+
+        let area = Rectangle::new(Point::new(-2, 6), Size::new(5, 5));
+        target.fill_solid(&area, Rgb565::GREEN).unwrap();
+
+        let framebuffer = target.framebuffer();
+        assert!(framebuffer.verify_region(0, 6, 3, 2, pixel::Rgb565(0x07E0)));
+        assert_eq!(framebuffer.get_pixel(3, 6), Some(pixel::Rgb565(0x0000)));
+        assert_eq!(framebuffer.get_pixel(0, 5), Some(pixel::Rgb565(0x0000)));
+    }
+
+    #[test]
+    fn test_fill_contiguous_follows_color_stream() {
+        let mut target = FramebufferTarget::new(MockFramebuffer::new(4, 4));
+
+        let area = Rectangle::new(Point::new(3, 0), Size::new(2, 2));
+        let colors = [Rgb565::RED, Rgb565::GREEN, Rgb565::BLUE, Rgb565::WHITE];
+        target.fill_contiguous(&area, colors).unwrap();
+
+        // The right-hand column is off screen and must consume its colors
+        let framebuffer = target.framebuffer();
+        assert_eq!(framebuffer.get_pixel(3, 0), Some(pixel::Rgb565(0xF800)));
+        assert_eq!(framebuffer.get_pixel(3, 1), Some(pixel::Rgb565(0x001F)));
+    }
+
+    #[test]
+    fn test_driver_draw_target() {
+        let mut driver = OTM8009ADriver::new(
+            MockDsiInterface::new(),
+            MockLtdcInterface::new(),
+            MockFramebuffer::new(LCD_WIDTH, LCD_HEIGHT),
+        );
+
+        let area = Rectangle::new(Point::new(10, 10), Size::new(2, 2));
+        assert_eq!(driver.fill_solid(&area, Rgb565::RED), Err(Otm8009aError::NotReady));
+
+        driver.init(OTM8009A_FORMAT_RGB565, OTM8009A_ORIENTATION_LANDSCAPE).unwrap();
+        driver.fill_solid(&area, Rgb565::RED).unwrap();
+        DrawTarget::clear(&mut driver, Rgb565::BLUE).unwrap();
+
+        assert!(driver.framebuffer().verify_region(0, 0, LCD_WIDTH, LCD_HEIGHT, pixel::Rgb565(0x001F)));
+    }
+
+    #[cfg(feature = "ili9341")]
+    #[test]
+    fn test_bus_driver_draw_target() {
+        use crate::ili9341::{ILI9341Driver, ILI9341_FORMAT_RGB565, ILI9341_ORIENTATION_LANDSCAPE};
+
+        let mut driver = ILI9341Driver::new(MockDisplayBus::new());
+        driver.init(ILI9341_FORMAT_RGB565, ILI9341_ORIENTATION_LANDSCAPE).unwrap();
+        driver.bus_mut().clear_history();
+
+        let area = Rectangle::new(Point::new(-5, 0), Size::new(10, 3));
+        driver.fill_solid(&area, Rgb565::RED).unwrap();
+
+        assert_eq!(driver.bus().writes.last(), Some(&BusWrite::Repeated(vec![0xF8, 0x00], 15)));
+    }
+}
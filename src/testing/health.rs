@@ -0,0 +1,154 @@
+//! Tests for panel health checks and ESD recovery
+
+#[cfg(test)]
+mod tests {
+    use crate::otm8009a::defs::*;
+    use crate::error::DsiError;
+    use crate::testing::mocks::*;
+    use crate::traits::*;
+
+    /// Power mode status of a panel showing the display: booster on, sleep
+    /// out, normal mode and display on
+    const STATUS_NORMAL: u8 = 0x9C;
+    /// Power mode status of a panel that was reset into sleep
+    const STATUS_SLEEP: u8 = 0x08;
+
+    fn healthy_driver() -> MockDriver {
+        let mut driver = init_driver(presets::STANDARD_PORTRAIT);
+        driver.dsi_mut().set_register(commands::GET_POWER_MODE, &[STATUS_NORMAL]);
+        driver.dsi_mut().set_register(commands::GET_DIAGNOSTIC_RESULT, &[0xC0]);
+        driver
+    }
+
+    fn sent(driver: &MockDriver) -> Vec<Vec<u8>> {
+        driver.dsi().commands_sent.iter().map(|command| command.params.clone()).collect()
+    }
+
+    #[test]
+    fn test_healthy_panel() {
+        let mut driver = healthy_driver();
+        let mut events = Vec::new();
+
+        let status = driver.check_health(|event| events.push(event)).unwrap();
+        assert_eq!(status, PanelStatus { power_mode: STATUS_NORMAL, diagnostic: 0xC0 });
+        assert_eq!(status.mode(), PowerMode::Normal);
+        assert!(status.registers_loaded() && status.functional());
+        assert!(events.is_empty());
+        assert_eq!(driver.dsi().reads, [0xDA, 0xDB, 0xDC, 0x0A, 0x0F]);
+    }
+
+    #[test]
+    fn test_recovery_restores_configuration() {
+        let mut driver = healthy_driver();
+        driver.set_brightness(0x40).unwrap();
+        driver.update_layer(0, |config| config.alpha = 200).unwrap();
+        let overlay = LayerConfig::new(1, driver.framebuffer(), 64, 64);
+        driver.configure_layer(overlay).unwrap();
+        driver.enable_tearing_effect(TearMode::VHBlank).unwrap();
+
+        // ESD resets the panel into sleep
+        driver.dsi_mut().set_register(commands::GET_POWER_MODE, &[STATUS_SLEEP]);
+        let mut events = Vec::new();
+        let status = driver.check_health(|event| events.push(event)).unwrap();
+
+        assert_eq!(events, [
+            HealthEvent::Lost { expected: PowerMode::Normal, status: Some(status) },
+            HealthEvent::Recovered(status),
+        ]);
+        assert!(driver.is_initialized());
+        assert_eq!(driver.power_mode(), PowerMode::Normal);
+        assert_eq!(driver.get_dimensions(), (LCD_HEIGHT, LCD_WIDTH));
+
+        let sent = sent(&driver);
+        assert!(sent.contains(&init_sequences::CMD_PORTRAIT.to_vec()));
+        assert!(sent.contains(&init_sequences::CMD_RGB565.to_vec()));
+        assert!(sent.ends_with(&[vec![0x51, 0x40], vec![0x35, 0x01]]));
+        assert_eq!(driver.brightness(), 0x40);
+        assert_eq!(driver.tear_mode(), Some(TearMode::VHBlank));
+        assert_eq!(driver.ltdc().get_layer_config(0).unwrap().alpha, 200);
+        assert_eq!(driver.ltdc().get_layer_config(1).unwrap().window_x1, 64);
+    }
+
+    #[test]
+    fn test_failed_diagnostic_triggers_recovery() {
+        let mut driver = healthy_driver();
+
+        // Still showing the display, but the registers did not load
+        driver.dsi_mut().set_register(commands::GET_DIAGNOSTIC_RESULT, &[0x40]);
+        let mut events = Vec::new();
+        driver.check_health(|event| events.push(event)).unwrap();
+
+        let status = PanelStatus { power_mode: STATUS_NORMAL, diagnostic: 0x40 };
+        assert!(!status.is_healthy());
+        assert_eq!(events[0], HealthEvent::Lost { expected: PowerMode::Normal, status: Some(status) });
+        assert_eq!(events.len(), 2);
+        assert!(sent(&driver).contains(&init_sequences::CMD_PORTRAIT.to_vec()));
+    }
+
+    #[test]
+    fn test_recovery_restores_power_mode() {
+        let mut driver = healthy_driver();
+        driver.enter_partial_mode(700, 799).unwrap();
+        driver.dsi_mut().set_register(commands::GET_POWER_MODE, &[STATUS_SLEEP]);
+
+        driver.check_health(|_| {}).unwrap();
+        assert_eq!(driver.power_mode(), PowerMode::PartialDisplay);
+        assert!(sent(&driver).ends_with(&[
+            vec![commands::SET_PARTIAL_AREA, 0x02, 0xBC, 0x03, 0x1F],
+            vec![commands::PARTIAL_MODE_ON],
+        ]));
+
+        driver.enter_idle_mode().unwrap();
+        driver.recover().unwrap();
+        assert_eq!(driver.power_mode(), PowerMode::IdleMode);
+        assert_eq!(sent(&driver).last(), Some(&vec![commands::IDLE_MODE_ON]));
+    }
+
+    #[test]
+    fn test_unresponsive_panel() {
+        let mut driver = healthy_driver();
+        driver.dsi_mut().set_should_fail(true);
+        let mut events = Vec::new();
+
+        let result = driver.check_health(|event| events.push(event));
+        assert_eq!(events, [HealthEvent::Lost { expected: PowerMode::Normal, status: None }]);
+        assert_eq!(result, Err(Otm8009aError::Dsi(DsiError::host(MockDsiError::SimulatedFailure))));
+    }
+
+    #[test]
+    fn test_sleeping_panel_is_left_alone() {
+        let mut driver = healthy_driver();
+        driver.enter_sleep().unwrap();
+        driver.dsi_mut().set_register(commands::GET_POWER_MODE, &[STATUS_SLEEP]);
+        let commands = driver.dsi().command_count();
+
+        let status = driver.check_health(|_| panic!("no recovery while asleep")).unwrap();
+        assert_eq!(status.mode(), PowerMode::Sleep);
+        assert_eq!(driver.dsi().command_count(), commands);
+
+        let mut driver = new_driver();
+        assert_eq!(driver.check_health(|_| {}), Err(Otm8009aError::NotReady));
+        assert_eq!(driver.recover(), Err(Otm8009aError::NotReady));
+    }
+
+    #[cfg(feature = "nt35510")]
+    #[test]
+    fn test_nt35510_recovery() {
+        use crate::nt35510::defs::NT35510_ID;
+        use crate::panel::{Panel, PanelDriver};
+
+        let mut panel: PanelDriver<_, _, MockFramebuffer> = PanelDriver::probe(
+            MockDsiInterface::with_id(0x00, NT35510_ID, 0x00),
+            MockLtdcInterface::new(),
+            MockFramebuffer::new(LCD_WIDTH, LCD_HEIGHT),
+        ).unwrap();
+        assert_eq!(panel.panel(), Panel::Nt35510);
+        panel.init_with(presets::STANDARD_LANDSCAPE).unwrap();
+
+        let mut events = 0;
+        panel.check_health(|_| events += 1).unwrap();
+        assert_eq!(events, 2);
+        assert!(panel.is_initialized());
+        assert_eq!(panel.get_dimensions(), (LCD_WIDTH, LCD_HEIGHT));
+    }
+}
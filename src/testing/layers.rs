@@ -0,0 +1,183 @@
+//! Tests for LTDC layer compositing
+
+#[cfg(test)]
+mod tests {
+    use crate::otm8009a::defs::*;
+    use crate::otm8009a::driver::OTM8009ADriver;
+    use crate::testing::mocks::*;
+    use crate::palette::Palette;
+    use crate::traits::{pixel::{Argb8888, L8}, *};
+
+    /// 64x64 ARGB overlay, e.g. a cursor, at (100, 50)
+    fn overlay(framebuffer: &MockFramebuffer<Argb8888>) -> LayerConfig {
+        let mut config = LayerConfig::new(1, framebuffer, 64, 64);
+        config.window_x0 = 100;
+        config.window_x1 = 164;
+        config.window_y0 = 50;
+        config.window_y1 = 114;
+        config
+    }
+
+    #[test]
+    fn test_init_configures_background_layer() {
+        let driver = init_driver(presets::STANDARD_LANDSCAPE);
+
+        let config = driver.layer_config(0).unwrap();
+        assert_eq!((config.window_x1, config.window_y1), (LCD_WIDTH, LCD_HEIGHT));
+        assert_eq!(config.blending, Blending::PixelAlphaTimesConstantAlpha);
+        assert!(config.enabled && !config.color_key_enabled);
+        assert!(driver.layer_config(1).is_none());
+    }
+
+    #[test]
+    fn test_overlay_layer() {
+        let mut driver = init_driver(presets::STANDARD_LANDSCAPE);
+        let cursor = MockFramebuffer::<Argb8888>::new(64, 64);
+
+        let mut config = overlay(&cursor);
+        config.alpha = 128;
+        config.blending = Blending::ConstantAlpha;
+        driver.configure_layer(config).unwrap();
+
+        driver.update_layer(1, |config| {
+            config.color_key_enabled = true;
+            config.color_key = 0x00FF00FF;
+            config.default_color = 0x80000000;
+        }).unwrap();
+
+        let sent = driver.ltdc().get_layer_config(1).unwrap();
+        assert_eq!(sent.pixel_format, PixelFormat::Argb8888);
+        assert_eq!(sent.framebuffer_address, cursor.get_buffer_ptr() as u32);
+        assert_eq!(sent.framebuffer_pitch, 64 * 4);
+        assert_eq!((sent.window_x0, sent.window_y0), (100, 50));
+        assert_eq!((sent.alpha, sent.blending), (128, Blending::ConstantAlpha));
+        assert!(sent.color_key_enabled);
+        assert_eq!((sent.color_key, sent.default_color), (0x00FF00FF, 0x80000000));
+
+        // The background is untouched
+        assert_eq!(driver.ltdc().get_layer_config(0).unwrap().window_x0, 0);
+    }
+
+    #[test]
+    fn test_enable_and_disable_layer() {
+        let mut driver = init_driver(presets::STANDARD_LANDSCAPE);
+        let cursor = MockFramebuffer::<Argb8888>::new(64, 64);
+        assert_eq!(driver.disable_layer(1), Err(Otm8009aError::InvalidConfig));
+
+        driver.configure_layer(overlay(&cursor)).unwrap();
+        driver.disable_layer(1).unwrap();
+        assert!(!driver.layer_config(1).unwrap().enabled);
+        assert!(!driver.ltdc().get_layer_config(1).unwrap().enabled);
+
+        driver.enable_layer(1).unwrap();
+        assert!(driver.ltdc().get_layer_config(1).unwrap().enabled);
+    }
+
+    #[test]
+    fn test_invalid_layers_are_rejected() {
+        let mut driver = init_driver(presets::STANDARD_LANDSCAPE);
+        let cursor = MockFramebuffer::<Argb8888>::new(64, 64);
+
+        let mut config = overlay(&cursor);
+        config.layer = LTDC_LAYER_COUNT;
+        assert_eq!(driver.configure_layer(config), Err(Otm8009aError::InvalidConfig));
+
+        let mut config = overlay(&cursor);
+        config.window_x1 = LCD_WIDTH + 1;
+        assert_eq!(driver.configure_layer(config), Err(Otm8009aError::InvalidConfig));
+
+        assert_eq!(driver.update_layer(1, |config| config.alpha = 0), Err(Otm8009aError::InvalidConfig));
+        assert_eq!(driver.update_layer(0, |config| config.window_x0 = LCD_WIDTH), Err(Otm8009aError::InvalidConfig));
+        assert_eq!(driver.layer_config(0).unwrap().window_x0, 0);
+    }
+
+    #[test]
+    fn test_indexed_framebuffer() {
+        let mut driver = OTM8009ADriver::new(
+            MockDsiInterface::new(),
+            MockLtdcInterface::new(),
+            MockFramebuffer::<L8>::new(LCD_WIDTH, LCD_HEIGHT),
+        );
+        let palette = Palette::from_rgb888(&[(0, 0, 0), (0xFF, 0xFF, 0xFF)]).unwrap();
+        assert_eq!(driver.load_palette(0, &palette), Err(Otm8009aError::NotReady));
+
+        driver.init(OTM8009A_FORMAT_RGB888, OTM8009A_ORIENTATION_LANDSCAPE).unwrap();
+        driver.load_palette(0, &palette).unwrap();
+        driver.set_pixel(1, 1, L8(1)).unwrap();
+
+        let config = driver.ltdc().get_layer_config(0).unwrap();
+        assert_eq!(config.pixel_format, PixelFormat::L8);
+        assert_eq!(config.framebuffer_pitch, LCD_WIDTH);
+        assert_eq!(driver.framebuffer().get_buffer_size(), LCD_WIDTH as usize * LCD_HEIGHT as usize);
+        assert_eq!(driver.ltdc().get_clut(0), Some(&[0x000000, 0xFFFFFF][..]));
+        assert_eq!(driver.framebuffer().get_pixel(1, 1), Some(L8(1)));
+    }
+
+    #[test]
+    fn test_palette_needs_indexed_layer() {
+        let mut driver = init_driver(presets::STANDARD_LANDSCAPE);
+        let palette = Palette::grayscale();
+
+        // Layer 0 is RGB565 and layer 1 is not configured
+        assert_eq!(driver.load_palette(0, &palette), Err(Otm8009aError::InvalidConfig));
+        assert_eq!(driver.load_palette(1, &palette), Err(Otm8009aError::InvalidConfig));
+
+        // AL44 only addresses 16 colors
+        let sprite = MockFramebuffer::<crate::traits::pixel::Al44>::new(16, 16);
+        driver.configure_layer(LayerConfig::new(1, &sprite, 16, 16)).unwrap();
+        assert_eq!(driver.load_palette(1, &palette), Err(Otm8009aError::InvalidConfig));
+
+        let palette = Palette::from_words(&[0; 16]).unwrap();
+        driver.load_palette(1, &palette).unwrap();
+        assert!(driver.ltdc().get_clut(0).is_none());
+        assert_eq!(driver.ltdc().get_clut(1).map(<[u32]>::len), Some(16));
+    }
+
+    #[test]
+    fn test_layer_config_matches_c_layout() {
+        use core::mem::{align_of, offset_of, size_of};
+
+        // Keep in sync with the C struct in c/avmgl_platform.h
+        assert_eq!(size_of::<LayerConfig>(), 48);
+        assert_eq!(align_of::<LayerConfig>(), 4);
+        assert_eq!(offset_of!(LayerConfig, layer), 0);
+        assert_eq!(offset_of!(LayerConfig, window_x0), 2);
+        assert_eq!(offset_of!(LayerConfig, window_x1), 4);
+        assert_eq!(offset_of!(LayerConfig, window_y0), 6);
+        assert_eq!(offset_of!(LayerConfig, window_y1), 8);
+        assert_eq!(offset_of!(LayerConfig, pixel_format), 12);
+        assert_eq!(offset_of!(LayerConfig, alpha), 16);
+        assert_eq!(offset_of!(LayerConfig, red_blue_swap), 17);
+        assert_eq!(offset_of!(LayerConfig, framebuffer_address), 20);
+        assert_eq!(offset_of!(LayerConfig, framebuffer_pitch), 24);
+        assert_eq!(offset_of!(LayerConfig, blending), 28);
+        assert_eq!(offset_of!(LayerConfig, default_color), 32);
+        assert_eq!(offset_of!(LayerConfig, color_key_enabled), 36);
+        assert_eq!(offset_of!(LayerConfig, color_key), 40);
+        assert_eq!(offset_of!(LayerConfig, enabled), 44);
+
+        assert_eq!(size_of::<PixelFormat>(), 4);
+        assert_eq!(size_of::<Blending>(), 4);
+    }
+
+    #[test]
+    fn test_c_header_values_match() {
+        let header = include_str!("../../c/avmgl_platform.h");
+        let values = [
+            ("AVMGL_PF_ARGB8888", PixelFormat::Argb8888 as u32),
+            ("AVMGL_PF_RGB888", PixelFormat::Rgb888 as u32),
+            ("AVMGL_PF_RGB565", PixelFormat::Rgb565 as u32),
+            ("AVMGL_PF_ARGB1555", PixelFormat::Argb1555 as u32),
+            ("AVMGL_PF_ARGB4444", PixelFormat::Argb4444 as u32),
+            ("AVMGL_PF_L8", PixelFormat::L8 as u32),
+            ("AVMGL_PF_AL44", PixelFormat::Al44 as u32),
+            ("AVMGL_PF_AL88", PixelFormat::Al88 as u32),
+            ("AVMGL_BLENDING_CONSTANT_ALPHA", Blending::ConstantAlpha as u32),
+            ("AVMGL_BLENDING_PIXEL_ALPHA_TIMES_CONSTANT_ALPHA", Blending::PixelAlphaTimesConstantAlpha as u32),
+        ];
+
+        for (name, value) in values {
+            assert!(header.contains(&format!("#define {} {}\n", name, value)), "{} is not {}", name, value);
+        }
+    }
+}
@@ -0,0 +1,213 @@
+//! Tests for the type-state driver lifecycle
+//!
+//! Only built with the `lifecycle` feature.
+
+#[cfg(all(test, feature = "lifecycle"))]
+mod tests {
+    use crate::otm8009a::defs::*;
+    use crate::otm8009a::driver::OTM8009ADriver;
+    use crate::error::DsiError;
+    use crate::otm8009a::lifecycle::{HealthCheckError, Otm8009a, Uninitialized};
+    use crate::testing::mocks::*;
+    use crate::traits::{pixel::Rgb565, DsiInterface, OutputPin};
+
+    type MockPanel = Otm8009a<Uninitialized, MockDsiInterface, MockLtdcInterface, MockFramebuffer>;
+
+    fn new_panel() -> MockPanel {
+        Otm8009a::new(
+            MockDsiInterface::new(),
+            MockLtdcInterface::new(),
+            MockFramebuffer::new(LCD_WIDTH, LCD_HEIGHT),
+        )
+    }
+
+    fn opcodes(driver: &OTM8009ADriver<MockDsiInterface, MockLtdcInterface, MockFramebuffer>) -> Vec<u8> {
+        driver.dsi().commands_sent.iter().map(|command| command.params[0]).collect()
+    }
+
+    #[test]
+    fn test_init_and_draw() {
+        let panel = Otm8009a::new(
+            MockDsiInterface::new(),
+            MockLtdcInterface::new(),
+            MockFramebuffer::new(LCD_HEIGHT, LCD_WIDTH),
+        );
+        let mut panel = panel.init(presets::STANDARD_PORTRAIT).unwrap();
+        assert!(panel.driver().is_initialized());
+        assert_eq!(panel.driver().get_dimensions(), (LCD_HEIGHT, LCD_WIDTH));
+
+        panel.clear(Rgb565(0x001F)).unwrap();
+        panel.fill_rect(0, 0, 4, 4, Rgb565(0xF800)).unwrap();
+        assert_eq!(panel.fill_rect(LCD_HEIGHT, 0, 1, 1, Rgb565(0)), Err(Otm8009aError::InvalidCoordinates));
+        assert_eq!(panel.driver().framebuffer().get_pixel(3, 3), Some(Rgb565(0xF800)));
+        assert_eq!(panel.driver().framebuffer().get_pixel(4, 4), Some(Rgb565(0x001F)));
+    }
+
+    #[test]
+    fn test_sleep_wake_cycle() {
+        let panel = new_panel().init(DisplayConfig::default()).unwrap();
+
+        let asleep = panel.sleep().unwrap();
+        assert_eq!(asleep.driver().power_mode(), PowerMode::Sleep);
+        assert!(opcodes(asleep.driver()).ends_with(&[0x28, 0x10]));
+
+        let mut platform = MockPlatformInterface::new("mock");
+        let off = asleep.power_off(&mut platform).unwrap();
+        assert!(platform.low_power_mode);
+        assert_eq!(off.driver().power_mode(), PowerMode::Off);
+
+        let mut panel = off.power_on(&mut platform).unwrap().wake().unwrap();
+        assert!(!platform.low_power_mode);
+        assert_eq!(panel.driver().power_mode(), PowerMode::Normal);
+        assert!(opcodes(panel.driver()).ends_with(&[0x11, 0x29]));
+
+        panel.enter_idle_mode().unwrap();
+        let asleep = panel.sleep().unwrap();
+        assert_eq!(asleep.driver().power_mode(), PowerMode::Sleep);
+    }
+
+    #[test]
+    fn test_failed_transition_returns_driver() {
+        let panel: MockPanel = OTM8009ADriver::new(
+            MockDsiInterface::with_id(0x00, 0x80, 0x00),
+            MockLtdcInterface::new(),
+            MockFramebuffer::new(LCD_WIDTH, LCD_HEIGHT),
+        ).into();
+
+        let failed = panel.init(DisplayConfig::default()).unwrap_err();
+        assert_eq!(failed.error, Otm8009aError::WrongPanelId(0x00));
+        assert!(!failed.driver.driver().is_initialized());
+
+        // Fix the wiring and retry with the same driver
+        let mut driver = failed.driver.into_driver();
+        driver.dsi_mut().set_register(commands::READ_ID1, &[OTM8009A_ID]);
+        let panel = Otm8009a::from(driver).init(DisplayConfig::default()).unwrap();
+        assert!(panel.driver().is_initialized());
+    }
+
+    #[test]
+    fn test_check_health_keeps_healthy_panel() {
+        let mut dsi = MockDsiInterface::new();
+        dsi.set_register(commands::GET_POWER_MODE, &[0x9C]);
+        dsi.set_register(commands::GET_DIAGNOSTIC_RESULT, &[0xC0]);
+        let panel: MockPanel = Otm8009a::new(dsi, MockLtdcInterface::new(), MockFramebuffer::new(LCD_WIDTH, LCD_HEIGHT));
+        let panel = panel.init(DisplayConfig::default()).unwrap();
+
+        let (panel, status) = panel.check_health(|_| panic!("healthy panel recovered")).unwrap();
+        assert!(status.is_healthy());
+        assert!(panel.driver().is_initialized());
+    }
+
+    #[test]
+    fn test_failed_recovery_returns_uninitialized() {
+        /// XRES line that stops responding after `pulses` resets
+        struct FlakyPin {
+            pulses: usize,
+        }
+
+        impl OutputPin for FlakyPin {
+            type Error = ();
+
+            fn set_low(&mut self) -> Result<(), ()> {
+                self.pulses = self.pulses.checked_sub(1).ok_or(())?;
+                Ok(())
+            }
+
+            fn set_high(&mut self) -> Result<(), ()> {
+                Ok(())
+            }
+        }
+
+        let driver = OTM8009ADriver::new(
+            MockDsiInterface::new(),
+            MockLtdcInterface::new(),
+            MockFramebuffer::<Rgb565>::new(LCD_WIDTH, LCD_HEIGHT),
+        ).with_reset_pin(FlakyPin { pulses: 1 });
+        let panel = Otm8009a::from(driver).init(DisplayConfig::default()).unwrap();
+
+        // The panel reads back as asleep, and the reset pin fails on recovery
+        let mut events = Vec::new();
+        let failed = match panel.check_health(|event| events.push(event)) {
+            Err(HealthCheckError::Reset(failed)) => failed,
+            _ => panic!("panel not reset"),
+        };
+        assert_eq!(failed.error, Otm8009aError::CommError);
+        assert_eq!(events.len(), 1);
+        assert!(!failed.driver.driver().is_initialized());
+
+        // Only init is allowed from here
+        let mut driver = failed.driver.into_driver();
+        assert_eq!(driver.set_pixel(0, 0, Rgb565(0)), Err(Otm8009aError::NotReady));
+    }
+
+    #[test]
+    fn test_failed_host_reset_stays_ready() {
+        /// DSI host that cannot be reset
+        struct StuckHost(MockDsiInterface);
+
+        impl DsiInterface for StuckHost {
+            type Error = MockDsiError;
+
+            fn send_dcs_command(&mut self, nb_params: usize, params: &[u8]) -> Result<(), Self::Error> {
+                self.0.send_dcs_command(nb_params, params)
+            }
+
+            fn delay_ms(&mut self, ms: u32) {
+                self.0.delay_ms(ms)
+            }
+
+            fn is_ready(&self) -> bool {
+                self.0.is_ready()
+            }
+
+            fn reset(&mut self) -> Result<(), Self::Error> {
+                Err(MockDsiError::SimulatedFailure)
+            }
+
+            fn set_max_return_packet_size(&mut self, size: u16) -> Result<(), Self::Error> {
+                self.0.set_max_return_packet_size(size)
+            }
+
+            fn dcs_read(&mut self, command: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+                self.0.dcs_read(command, buffer)
+            }
+
+            fn wait_for_tearing_effect(&mut self) -> Result<(), Self::Error> {
+                self.0.wait_for_tearing_effect()
+            }
+        }
+
+        let panel = Otm8009a::new(
+            StuckHost(MockDsiInterface::new()),
+            MockLtdcInterface::new(),
+            MockFramebuffer::<Rgb565>::new(LCD_WIDTH, LCD_HEIGHT),
+        );
+        let panel = panel.init(DisplayConfig::default()).unwrap();
+
+        // The panel reads back as asleep, but recovery never gets to init
+        let failed = match panel.check_health(|_| {}) {
+            Err(HealthCheckError::Ready(failed)) => failed,
+            _ => panic!("panel not kept ready"),
+        };
+        assert_eq!(failed.error, Otm8009aError::Dsi(DsiError::host(MockDsiError::SimulatedFailure)));
+        let mut panel = failed.driver;
+        assert!(panel.driver().is_initialized());
+        panel.set_pixel(0, 0, Rgb565(0xFFFF)).unwrap();
+    }
+
+    #[test]
+    fn test_sleep_config_rejected() {
+        let config = presets::STANDARD_LANDSCAPE.with_power_mode(PowerMode::Sleep).unwrap();
+        let failed = new_panel().init(config).unwrap_err();
+        assert_eq!(failed.error, Otm8009aError::InvalidConfig);
+        assert!(failed.driver.driver().dsi().commands_sent.is_empty());
+    }
+
+    #[test]
+    fn test_into_driver_keeps_state() {
+        let panel = new_panel().init(DisplayConfig::default()).unwrap();
+        let mut driver = panel.into_driver();
+        assert!(driver.is_initialized());
+        driver.set_pixel(0, 0, Rgb565(0xFFFF)).unwrap();
+    }
+}
@@ -5,11 +5,18 @@
 
 extern crate alloc;
 
-use alloc::{vec, vec::Vec};
+use alloc::{boxed::Box, rc::Rc, vec, vec::Vec};
+use core::cell::{Ref, RefCell};
 
-use crate::traits::*;
-use crate::testing::traits::*;
-use crate::otm8009a::defs::{LCD_WIDTH, LCD_HEIGHT};
+use crate::traits::{pixel::Rgb565, *};
+/// DCS command recorded by `MockDsiInterface`
+pub use crate::testing::traits::DcsCommand;
+use crate::otm8009a::defs::{commands, DisplayConfig, Orientation, LCD_WIDTH, LCD_HEIGHT, OTM8009A_ID};
+use crate::otm8009a::driver::Otm8009aSpec;
+use crate::backlight::DynBacklight;
+#[cfg(feature = "dbi")]
+use crate::dbi_panel::{DbiPanelDriver, DbiPanelSpec};
+use crate::dsi_panel::{DsiPanelDriver, PanelSpec};
 
 /// Mock DSI interface for testing
 #[derive(Debug)]
@@ -17,20 +24,24 @@ pub struct MockDsiInterface {
     pub commands_sent: Vec<DcsCommand>,
     pub delays_requested: Vec<u32>,
     pub should_fail: bool,
+    /// Fail only writes of this DCS command
+    pub fail_on: Option<u8>,
     pub is_ready: bool,
+    /// Responses returned by reads, keyed by DCS command
+    pub registers: Vec<(u8, Vec<u8>)>,
+    /// DCS commands read so far
+    pub reads: Vec<u8>,
+    pub max_return_packet_size: u16,
+    /// Number of commands sent before each tearing effect wait
+    pub tearing_effect_waits: Vec<usize>,
 }
 
-#[derive(Debug, Clone)]
-pub struct DcsCommand {
-    pub nb_params: usize,
-    pub params: Vec<u8>,
-}
-
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MockDsiError {
     NotReady,
     SimulatedFailure,
     CommandTooLong,
+    InvalidRead,
 }
 
 impl MockDsiInterface {
@@ -39,10 +50,34 @@ impl MockDsiInterface {
             commands_sent: Vec::new(),
             delays_requested: Vec::new(),
             should_fail: false,
+            fail_on: None,
             is_ready: true,
+            registers: vec![
+                (commands::READ_ID1, vec![OTM8009A_ID]),
+                (commands::READ_ID2, vec![0x00]),
+                (commands::READ_ID3, vec![0x00]),
+            ],
+            reads: Vec::new(),
+            max_return_packet_size: 1,
+            tearing_effect_waits: Vec::new(),
         }
     }
     
+    /// Mock a panel answering RDID1..RDID3 with the given bytes
+    pub fn with_id(id1: u8, id2: u8, id3: u8) -> Self {
+        let mut dsi = Self::new();
+        dsi.set_register(commands::READ_ID1, &[id1]);
+        dsi.set_register(commands::READ_ID2, &[id2]);
+        dsi.set_register(commands::READ_ID3, &[id3]);
+        dsi
+    }
+    
+    /// Program the response to reads of `command`
+    pub fn set_register(&mut self, command: u8, value: &[u8]) {
+        self.registers.retain(|(c, _)| *c != command);
+        self.registers.push((command, value.to_vec()));
+    }
+    
     pub fn set_should_fail(&mut self, fail: bool) {
         self.should_fail = fail;
     }
@@ -54,6 +89,8 @@ impl MockDsiInterface {
     pub fn clear_history(&mut self) {
         self.commands_sent.clear();
         self.delays_requested.clear();
+        self.reads.clear();
+        self.tearing_effect_waits.clear();
     }
     
     pub fn get_last_command(&self) -> Option<&DcsCommand> {
@@ -65,11 +102,17 @@ impl MockDsiInterface {
     }
 }
 
+impl Default for MockDsiInterface {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl DsiInterface for MockDsiInterface {
     type Error = MockDsiError;
     
     fn send_dcs_command(&mut self, nb_params: usize, params: &[u8]) -> Result<(), Self::Error> {
-        if self.should_fail {
+        if self.should_fail || self.fail_on.is_some_and(|opcode| params.first() == Some(&opcode)) {
             return Err(MockDsiError::SimulatedFailure);
         }
         
@@ -105,6 +148,116 @@ impl DsiInterface for MockDsiInterface {
         self.is_ready = true;
         Ok(())
     }
+    
+    fn set_max_return_packet_size(&mut self, size: u16) -> Result<(), Self::Error> {
+        if self.should_fail {
+            return Err(MockDsiError::SimulatedFailure);
+        }
+        
+        self.max_return_packet_size = size;
+        Ok(())
+    }
+    
+    fn dcs_read(&mut self, command: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        if self.should_fail {
+            return Err(MockDsiError::SimulatedFailure);
+        }
+        
+        if buffer.len() > self.max_return_packet_size as usize {
+            return Err(MockDsiError::InvalidRead);
+        }
+        
+        self.reads.push(command);
+        buffer.fill(0);
+        if let Some((_, value)) = self.registers.iter().find(|(c, _)| *c == command) {
+            let len = core::cmp::min(buffer.len(), value.len());
+            buffer[..len].copy_from_slice(&value[..len]);
+        }
+        Ok(())
+    }
+    
+    fn wait_for_tearing_effect(&mut self) -> Result<(), Self::Error> {
+        if self.should_fail {
+            return Err(MockDsiError::SimulatedFailure);
+        }
+
+        self.tearing_effect_waits.push(self.commands_sent.len());
+        Ok(())
+    }
+}
+
+/// Transfer recorded by `MockDisplayBus`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BusWrite {
+    Command(u8),
+    Data(Vec<u8>),
+    /// One pixel streamed `count` times
+    Repeated(Vec<u8>, u32),
+}
+
+/// Mock DBI bus for testing
+#[derive(Debug, Default)]
+pub struct MockDisplayBus {
+    pub writes: Vec<BusWrite>,
+    pub delays_requested: Vec<u32>,
+    pub should_fail: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MockBusError {
+    SimulatedFailure,
+}
+
+impl MockDisplayBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_should_fail(&mut self, fail: bool) {
+        self.should_fail = fail;
+    }
+
+    pub fn clear_history(&mut self) {
+        self.writes.clear();
+        self.delays_requested.clear();
+    }
+
+    /// Command bytes sent so far, in order
+    pub fn commands(&self) -> Vec<u8> {
+        self.writes.iter().filter_map(|write| match write {
+            BusWrite::Command(command) => Some(*command),
+            _ => None,
+        }).collect()
+    }
+
+    fn record(&mut self, write: BusWrite) -> Result<(), MockBusError> {
+        if self.should_fail {
+            return Err(MockBusError::SimulatedFailure);
+        }
+
+        self.writes.push(write);
+        Ok(())
+    }
+}
+
+impl DisplayBus for MockDisplayBus {
+    type Error = MockBusError;
+
+    fn write_command(&mut self, command: u8) -> Result<(), Self::Error> {
+        self.record(BusWrite::Command(command))
+    }
+
+    fn write_data(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+        self.record(BusWrite::Data(data.to_vec()))
+    }
+
+    fn write_repeated(&mut self, pixel: &[u8], count: u32) -> Result<(), Self::Error> {
+        self.record(BusWrite::Repeated(pixel.to_vec(), count))
+    }
+
+    fn delay_ms(&mut self, ms: u32) {
+        self.delays_requested.push(ms);
+    }
 }
 
 /// Mock LTDC interface for testing
@@ -113,11 +266,13 @@ pub struct MockLtdcInterface {
     pub layer_configs: Vec<LayerConfig>,
     pub enabled: bool,
     pub framebuffer_addresses: Vec<(u8, u32)>,
+    pub cluts: Vec<(u8, Vec<u32>)>,
     pub should_fail: bool,
     pub dimensions: (u16, u16),
+    pub vblank_waits: u32,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MockLtdcError {
     InvalidLayer,
     SimulatedFailure,
@@ -130,8 +285,10 @@ impl MockLtdcInterface {
             layer_configs: Vec::new(),
             enabled: false,
             framebuffer_addresses: Vec::new(),
+            cluts: Vec::new(),
             should_fail: false,
             dimensions: (LCD_WIDTH, LCD_HEIGHT),
+            vblank_waits: 0,
         }
     }
     
@@ -139,6 +296,10 @@ impl MockLtdcInterface {
         self.should_fail = fail;
     }
     
+    pub fn get_clut(&self, layer: u8) -> Option<&[u32]> {
+        self.cluts.iter().find(|(l, _)| *l == layer).map(|(_, colors)| colors.as_slice())
+    }
+    
     pub fn get_layer_config(&self, layer: u8) -> Option<&LayerConfig> {
         self.layer_configs.iter().find(|config| config.layer == layer)
     }
@@ -146,6 +307,24 @@ impl MockLtdcInterface {
     pub fn is_enabled(&self) -> bool {
         self.enabled
     }
+
+    fn set_layer_enabled(&mut self, layer: u8, enabled: bool) -> Result<(), MockLtdcError> {
+        if self.should_fail {
+            return Err(MockLtdcError::SimulatedFailure);
+        }
+
+        let config = self.layer_configs.iter_mut()
+            .find(|config| config.layer == layer)
+            .ok_or(MockLtdcError::InvalidLayer)?;
+        config.enabled = enabled;
+        Ok(())
+    }
+}
+
+impl Default for MockLtdcInterface {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl LtdcInterface for MockLtdcInterface {
@@ -186,6 +365,28 @@ impl LtdcInterface for MockLtdcInterface {
         Ok(())
     }
     
+    fn enable_layer(&mut self, layer: u8) -> Result<(), Self::Error> {
+        self.set_layer_enabled(layer, true)
+    }
+
+    fn disable_layer(&mut self, layer: u8) -> Result<(), Self::Error> {
+        self.set_layer_enabled(layer, false)
+    }
+    
+    fn load_clut(&mut self, layer: u8, colors: &[u32]) -> Result<(), Self::Error> {
+        if self.should_fail {
+            return Err(MockLtdcError::SimulatedFailure);
+        }
+
+        if colors.len() > 256 {
+            return Err(MockLtdcError::InvalidConfiguration);
+        }
+
+        self.cluts.retain(|(l, _)| *l != layer);
+        self.cluts.push((layer, colors.to_vec()));
+        Ok(())
+    }
+    
     fn set_framebuffer(&mut self, layer: u8, address: u32) -> Result<(), Self::Error> {
         if self.should_fail {
             return Err(MockLtdcError::SimulatedFailure);
@@ -206,20 +407,29 @@ impl LtdcInterface for MockLtdcInterface {
     fn get_dimensions(&self) -> (u16, u16) {
         self.dimensions
     }
+
+    fn wait_for_vblank(&mut self) -> Result<(), Self::Error> {
+        if self.should_fail {
+            return Err(MockLtdcError::SimulatedFailure);
+        }
+
+        self.vblank_waits += 1;
+        Ok(())
+    }
 }
 
 /// Mock framebuffer for testing
 #[derive(Debug)]
-pub struct MockFramebuffer {
-    buffer: Vec<u16>,
+pub struct MockFramebuffer<P: PixelType = Rgb565> {
+    buffer: Vec<P>,
     width: u16,
     height: u16,
 }
 
-impl MockFramebuffer {
+impl<P: PixelType> MockFramebuffer<P> {
     pub fn new(width: u16, height: u16) -> Self {
         let size = (width as usize) * (height as usize);
-        let buffer = vec![0; size]; // Initialize to black
+        let buffer = vec![P::default(); size]; // Initialize to black
         
         Self {
             buffer,
@@ -228,7 +438,7 @@ impl MockFramebuffer {
         }
     }
     
-    pub fn get_pixel(&self, x: u16, y: u16) -> Option<u16> {
+    pub fn get_pixel(&self, x: u16, y: u16) -> Option<P> {
         if x >= self.width || y >= self.height {
             return None;
         }
@@ -238,8 +448,10 @@ impl MockFramebuffer {
     }
 }
 
-impl FramebufferInterface for MockFramebuffer {
-    fn fill_rect(&mut self, x: u16, y: u16, width: u16, height: u16, color: u16) {
+impl<P: PixelType> FramebufferInterface for MockFramebuffer<P> {
+    type Pixel = P;
+
+    fn fill_rect(&mut self, x: u16, y: u16, width: u16, height: u16, color: P) {
         let end_x = core::cmp::min(x + width, self.width);
         let end_y = core::cmp::min(y + height, self.height);
         
@@ -253,7 +465,7 @@ impl FramebufferInterface for MockFramebuffer {
         }
     }
     
-    fn set_pixel(&mut self, x: u16, y: u16, color: u16) {
+    fn set_pixel(&mut self, x: u16, y: u16, color: P) {
         if x < self.width && y < self.height {
             let index = (y as usize) * (self.width as usize) + (x as usize);
             if let Some(pixel) = self.buffer.get_mut(index) {
@@ -262,7 +474,7 @@ impl FramebufferInterface for MockFramebuffer {
         }
     }
     
-    fn clear(&mut self, color: u16) {
+    fn clear(&mut self, color: P) {
         for pixel in self.buffer.iter_mut() {
             *pixel = color;
         }
@@ -271,13 +483,22 @@ impl FramebufferInterface for MockFramebuffer {
     fn get_dimensions(&self) -> (u16, u16) {
         (self.width, self.height)
     }
+
+    fn get_row(&self, y: u16) -> Option<&[P]> {
+        if y >= self.height {
+            return None;
+        }
+
+        let start = (y as usize) * (self.width as usize);
+        self.buffer.get(start..start + self.width as usize)
+    }
     
-    fn get_buffer_ptr(&self) -> *const u16 {
+    fn get_buffer_ptr(&self) -> *const P {
         self.buffer.as_ptr()
     }
     
     fn get_buffer_size(&self) -> usize {
-        self.buffer.len() * 2 // 2 bytes per u16
+        self.buffer.len() * P::BYTES_PER_PIXEL
     }
 }
 
@@ -344,4 +565,139 @@ impl PlatformInterface for MockPlatformInterface {
         self.low_power_mode = false;
         Ok(())
     }
-}
\ No newline at end of file
+}
+/// What a `MockBacklight` was asked to do
+#[derive(Debug, Default)]
+pub struct MockBacklightState {
+    /// Every switch on or off, in order
+    pub switches: Vec<bool>,
+    pub levels: Vec<u8>,
+    pub should_fail: bool,
+}
+
+/// Mock board backlight for testing
+///
+/// Clones share their state, so a test can keep one while a driver holds
+/// another.
+#[derive(Debug, Default, Clone)]
+pub struct MockBacklight {
+    state: Rc<RefCell<MockBacklightState>>,
+}
+
+#[derive(Debug)]
+pub struct MockBacklightError;
+
+impl MockBacklight {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn state(&self) -> Ref<'_, MockBacklightState> {
+        self.state.borrow()
+    }
+
+    /// Get the level last set
+    pub fn level(&self) -> Option<u8> {
+        self.state().levels.last().copied()
+    }
+
+    pub fn set_should_fail(&self, fail: bool) {
+        self.state.borrow_mut().should_fail = fail;
+    }
+}
+
+impl Backlight for MockBacklight {
+    type Error = MockBacklightError;
+
+    fn set_enabled(&mut self, enabled: bool) -> Result<(), Self::Error> {
+        let mut state = self.state.borrow_mut();
+        if state.should_fail {
+            return Err(MockBacklightError);
+        }
+
+        state.switches.push(enabled);
+        Ok(())
+    }
+
+    fn set_level(&mut self, level: u8) -> Result<(), Self::Error> {
+        let mut state = self.state.borrow_mut();
+        if state.should_fail {
+            return Err(MockBacklightError);
+        }
+
+        state.levels.push(level);
+        Ok(())
+    }
+}
+
+/// Mock GPIO output recording every level driven
+#[derive(Debug, Default)]
+pub struct MockOutputPin {
+    pub states: Vec<bool>,
+}
+
+impl MockOutputPin {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the level last driven, `true` for high
+    pub fn is_high(&self) -> Option<bool> {
+        self.states.last().copied()
+    }
+}
+
+impl OutputPin for MockOutputPin {
+    type Error = core::convert::Infallible;
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.states.push(false);
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.states.push(true);
+        Ok(())
+    }
+}
+
+/// OTM8009A driver on the mock DSI host, LTDC and framebuffer
+pub type MockDriver =
+    DsiPanelDriver<Otm8009aSpec, MockDsiInterface, MockLtdcInterface, MockFramebuffer>;
+
+/// Driver for the panel `S` answering on `dsi`, with a framebuffer sized for
+/// `orientation`
+///
+/// The driver tests build their drivers here, so the framebuffer always
+/// matches the orientation a test initializes.
+pub fn new_panel_driver<S>(
+    dsi: MockDsiInterface,
+    orientation: Orientation,
+    backlight: Option<MockBacklight>,
+) -> DsiPanelDriver<S, MockDsiInterface, MockLtdcInterface, MockFramebuffer>
+where
+    S: PanelSpec + Default,
+{
+    let spec = S::default();
+    let (width, height) = orientation.dimensions(spec.dimensions());
+    let backlight = backlight.map(|backlight| Box::leak(Box::new(backlight)) as &mut dyn DynBacklight);
+    DsiPanelDriver::build(spec, dsi, MockLtdcInterface::new(), MockFramebuffer::new(width, height), backlight)
+}
+
+/// Uninitialized OTM8009A driver with a landscape framebuffer
+pub fn new_driver() -> MockDriver {
+    new_panel_driver(MockDsiInterface::new(), Orientation::Landscape, None)
+}
+
+/// OTM8009A driver brought up with `config`
+pub fn init_driver(config: DisplayConfig) -> MockDriver {
+    let mut driver = new_panel_driver(MockDsiInterface::new(), config.orientation(), None);
+    driver.init_with(config).unwrap();
+    driver
+}
+
+/// Uninitialized driver for the DBI panel `S` on a mock bus
+#[cfg(feature = "dbi")]
+pub fn new_dbi_driver<S: DbiPanelSpec + Default>() -> DbiPanelDriver<S, MockDisplayBus> {
+    DbiPanelDriver::new(MockDisplayBus::new())
+}
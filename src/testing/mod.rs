@@ -3,13 +3,40 @@
 //! This module provides mock implementations of hardware interfaces
 //! for testing the OTM8009A display driver without actual hardware.
 
+pub mod backlight;
+pub mod config;
+pub mod dbi;
+pub mod driver;
+pub mod errors;
+// The footprint and NIF tests predate these lints; allow them rather than
+// rewrite the tests
+#[allow(clippy::needless_borrows_for_generic_args)]
 pub mod footprint;
+pub mod framebuffer;
+pub mod graphics;
+pub mod health;
+pub mod gram;
+pub mod ili9341;
+pub mod layers;
+pub mod lifecycle;
 pub mod mocks;
+#[allow(unused_imports, clippy::manual_range_contains, clippy::identity_op, clippy::erasing_op)]
 pub mod nifs;
+pub mod nt35510;
+pub mod palette;
+pub mod platform;
+pub mod power;
+pub mod reset;
+pub mod panel;
+pub mod script;
+pub mod st7789;
+pub mod swapchain;
 pub mod traits;
 
 // Re-exports for easy testing
+#[allow(unused_imports)]
 pub use footprint::*;
 pub use mocks::*;
+#[allow(unused_imports)]
 pub use nifs::*;
 pub use traits::*;
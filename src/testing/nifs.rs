@@ -6,7 +6,7 @@
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::otm8009a::defs::{LCD_WIDTH, LCD_HEIGHT};
+    use crate::otm8009a::defs::{ColorFormat, DisplayConfig, Orientation, LCD_WIDTH, LCD_HEIGHT};
     
     // Helper to create mock Context and Term values for testing
     // Since we can't actually create real avmnif-rs types in tests,
@@ -14,13 +14,15 @@ mod tests {
     
     // Extract just the validation logic from the NIFs for testing
     fn validate_init_tuple(width: i32, height: i32, orientation: i32) -> Result<(), &'static str> {
-        if width <= 0 || height <= 0 || width > 1024 || height > 1024 {
-            return Err("Invalid dimensions");
-        }
-        
-        if orientation < 0 || orientation > 3 {
-            return Err("Invalid orientation");
-        }
+        let orientation = u32::try_from(orientation).ok()
+            .and_then(Orientation::from_u32)
+            .ok_or("Invalid orientation")?;
+        let width = u16::try_from(width).map_err(|_| "Invalid dimensions")?;
+        let height = u16::try_from(height).map_err(|_| "Invalid dimensions")?;
+        // The size is checked against the panel once the driver inits
+        DisplayConfig::with_dimensions(width, height, orientation, ColorFormat::Rgb565)
+            .and_then(|config| config.dimensions_on((LCD_WIDTH, LCD_HEIGHT)))
+            .ok_or("Invalid dimensions")?;
         
         Ok(())
     }
@@ -87,8 +89,8 @@ mod tests {
     fn test_init_validation() {
         // Valid cases
         assert!(validate_init_tuple(800, 480, 1).is_ok());
-        assert!(validate_init_tuple(320, 240, 0).is_ok());
-        assert!(validate_init_tuple(1024, 768, 3).is_ok());
+        assert!(validate_init_tuple(480, 800, 0).is_ok());
+        assert!(validate_init_tuple(800, 480, 3).is_ok());
         
         // Invalid dimensions
         assert!(validate_init_tuple(-1, 480, 1).is_err());
@@ -96,6 +98,11 @@ mod tests {
         assert!(validate_init_tuple(0, 480, 1).is_err());
         assert!(validate_init_tuple(2000, 480, 1).is_err());
         
+        // Dimensions that don't match the orientation
+        assert!(validate_init_tuple(480, 800, 1).is_err());
+        assert!(validate_init_tuple(800, 480, 0).is_err());
+        assert!(validate_init_tuple(320, 240, 0).is_err());
+        
         // Invalid orientation
         assert!(validate_init_tuple(800, 480, -1).is_err());
         assert!(validate_init_tuple(800, 480, 4).is_err());
@@ -0,0 +1,110 @@
+//! Tests for the NT35510 driver
+//!
+//! Drives `NT35510Driver` end to end against the mock hardware interfaces.
+//! Only built with the `nt35510` feature.
+
+#[cfg(all(test, feature = "nt35510"))]
+mod tests {
+    use crate::nt35510::defs::*;
+    use crate::nt35510::driver::{NT35510Driver, Nt35510Spec};
+    use crate::testing::mocks::*;
+    use crate::traits::pixel::Rgb565;
+
+    type MockNt35510 = NT35510Driver<MockDsiInterface, MockLtdcInterface, MockFramebuffer>;
+
+    fn nt35510_driver(orientation: Orientation) -> MockNt35510 {
+        new_panel_driver::<Nt35510Spec>(MockDsiInterface::with_id(0x00, NT35510_ID, 0x00), orientation, None)
+    }
+
+    fn sent(driver: &MockNt35510) -> Vec<&[u8]> {
+        driver.dsi().commands_sent.iter()
+            .map(|command| command.params.as_slice())
+            .collect()
+    }
+
+    #[test]
+    fn test_init_sends_bsp_sequence() {
+        let mut driver = nt35510_driver(Orientation::Landscape);
+        driver.init(NT35510_FORMAT_RGB565, NT35510_ORIENTATION_LANDSCAPE).unwrap();
+
+        assert!(driver.is_initialized());
+        assert_eq!(driver.get_dimensions(), (LCD_WIDTH, LCD_HEIGHT));
+
+        let sent = sent(&driver);
+        assert_eq!(sent[0], &init_sequences::CMD_PAGE1[..]);
+        let position = |payload: &[u8]| sent.iter().position(|sent| *sent == payload).unwrap();
+        assert!(position(&init_sequences::CMD_PAGE0) < position(&[commands::SLEEP_OUT]));
+        assert!(position(&[commands::SLEEP_OUT]) < position(&init_sequences::CMD_RGB565));
+        assert!(position(&init_sequences::CMD_LANDSCAPE) < position(&[commands::DISPLAY_ON]));
+        assert_eq!(sent.last(), Some(&&[commands::WRITE_MEMORY_START][..]));
+    }
+
+    #[test]
+    fn test_init_rejects_other_panels() {
+        let mut driver = NT35510Driver::new(
+            MockDsiInterface::new(),
+            MockLtdcInterface::new(),
+            MockFramebuffer::<Rgb565>::new(LCD_WIDTH, LCD_HEIGHT),
+        );
+
+        assert_eq!(
+            driver.init(NT35510_FORMAT_RGB565, NT35510_ORIENTATION_LANDSCAPE),
+            Err(Nt35510Error::WrongPanelId(0x00))
+        );
+        assert!(!driver.is_initialized());
+    }
+
+    #[test]
+    fn test_init_rejects_rgb666() {
+        let mut driver = nt35510_driver(Orientation::Landscape);
+
+        assert_eq!(driver.init(0x66, NT35510_ORIENTATION_LANDSCAPE), Err(Nt35510Error::InvalidConfig));
+    }
+
+    #[test]
+    fn test_portrait_swaps_dimensions() {
+        let mut driver = nt35510_driver(Orientation::Portrait);
+        driver.init(NT35510_FORMAT_RGB565, NT35510_ORIENTATION_PORTRAIT).unwrap();
+
+        assert_eq!(driver.get_dimensions(), (LCD_HEIGHT, LCD_WIDTH));
+    }
+
+    #[test]
+    fn test_brightness_and_sleep() {
+        let mut driver = nt35510_driver(Orientation::Landscape);
+        assert_eq!(driver.set_brightness(0x40), Err(Nt35510Error::NotReady));
+
+        driver.init(NT35510_FORMAT_RGB565, NT35510_ORIENTATION_LANDSCAPE).unwrap();
+        driver.dsi_mut().clear_history();
+
+        driver.set_brightness(0x40).unwrap();
+        driver.enter_sleep().unwrap();
+        driver.exit_sleep().unwrap();
+
+        assert_eq!(sent(&driver), vec![
+            &[commands::WRITE_DISPLAY_BRIGHTNESS, 0x40][..],
+            &[commands::DISPLAY_OFF][..],
+            &[commands::SLEEP_IN][..],
+            &[commands::SLEEP_OUT][..],
+            &[commands::DISPLAY_ON][..],
+        ]);
+    }
+
+    #[test]
+    fn test_partial_mode() {
+        let mut driver = nt35510_driver(Orientation::Portrait);
+        driver.init(NT35510_FORMAT_RGB565, NT35510_ORIENTATION_PORTRAIT).unwrap();
+        driver.dsi_mut().clear_history();
+
+        // Portrait has 800 rows
+        driver.enter_partial_mode(700, 799).unwrap();
+        assert_eq!(driver.power_mode(), PowerMode::PartialDisplay);
+        driver.enter_normal_mode().unwrap();
+
+        assert_eq!(sent(&driver), vec![
+            &[commands::SET_PARTIAL_AREA, 0x02, 0xBC, 0x03, 0x1F][..],
+            &[commands::PARTIAL_MODE_ON][..],
+            &[commands::NORMAL_MODE_ON][..],
+        ]);
+    }
+}
@@ -0,0 +1,48 @@
+//! Tests for CLUT palettes
+
+#[cfg(test)]
+mod tests {
+    use crate::palette::*;
+    use crate::traits::pixel::L8;
+
+    #[test]
+    fn test_palette_from_rgb888() {
+        let palette = Palette::from_rgb888(&[(0, 0, 0), (0xFF, 0x80, 0x01)]).unwrap();
+
+        assert_eq!(palette.as_words(), &[0x000000, 0xFF8001]);
+        assert_eq!(palette.get(1), Some((0xFF, 0x80, 0x01)));
+        assert_eq!(palette.get(2), None);
+        assert_eq!(Palette::from_words(&[0xFFFF8001]).unwrap().as_words(), &[0xFF8001]);
+
+        assert!(Palette::from_rgb888(&[(0, 0, 0); CLUT_SIZE + 1]).is_none());
+        assert!(Palette::from_words(&[0; CLUT_SIZE + 1]).is_none());
+    }
+
+    #[test]
+    fn test_push_and_set() {
+        let mut palette = Palette::new();
+        assert!(palette.is_empty());
+        assert_eq!(palette.push(1, 2, 3), Some(L8(0)));
+
+        palette.set(9, 0xFF, 0, 0);
+        assert_eq!(palette.len(), 10);
+        assert_eq!(palette.get(5), Some((0, 0, 0)));
+
+        let mut palette = Palette::grayscale();
+        assert_eq!(palette.len(), CLUT_SIZE);
+        assert_eq!(palette.get(0x42), Some((0x42, 0x42, 0x42)));
+        assert_eq!(palette.push(0, 0, 0), None);
+    }
+
+    #[test]
+    fn test_rgb332() {
+        let palette = Palette::rgb332();
+
+        let index = Palette::rgb332_index(0xFF, 0x00, 0xFF);
+        assert_eq!(index, L8(0xE3));
+        assert_eq!(palette.get(index.0), Some((0xFF, 0x00, 0xFF)));
+        assert_eq!(palette.get(0xFF), Some((0xFF, 0xFF, 0xFF)));
+        assert_eq!(palette.nearest(0xFE, 0x01, 0xF0), Some(index));
+        assert_eq!(Palette::new().nearest(0, 0, 0), None);
+    }
+}
@@ -0,0 +1,71 @@
+//! Tests for panel detection and the enum-dispatched driver
+
+#[cfg(test)]
+mod tests {
+    use crate::error::{DisplayError, DsiError, ErrorContext, InitStage};
+    use crate::otm8009a::defs::*;
+    use crate::panel::*;
+    use crate::testing::mocks::*;
+    use crate::traits::{pixel::Rgb565, PanelId};
+
+    type MockPanel = PanelDriver<MockDsiInterface, MockLtdcInterface, MockFramebuffer>;
+
+    fn probe(dsi: MockDsiInterface) -> Result<MockPanel, DisplayError<MockDsiError, MockLtdcError>> {
+        PanelDriver::probe(dsi, MockLtdcInterface::new(), MockFramebuffer::new(LCD_WIDTH, LCD_HEIGHT))
+    }
+
+    #[test]
+    fn test_detects_otm8009a() {
+        let mut dsi = MockDsiInterface::new();
+        assert_eq!(detect::<_, MockLtdcError>(&mut dsi), Ok(Panel::Otm8009a));
+
+        let mut panel = probe(MockDsiInterface::new()).unwrap();
+        assert_eq!(panel.panel(), Panel::Otm8009a);
+
+        panel.init(OTM8009A_FORMAT_RGB565, OTM8009A_ORIENTATION_LANDSCAPE).unwrap();
+        assert!(panel.is_initialized());
+        panel.fill_rect(0, 0, 2, 2, Rgb565(0xF800)).unwrap();
+        assert_eq!(panel.framebuffer().get_pixel(1, 1), Some(Rgb565(0xF800)));
+    }
+
+    #[cfg(feature = "nt35510")]
+    #[test]
+    fn test_detects_nt35510() {
+        use crate::nt35510::defs::NT35510_ID;
+
+        let dsi = MockDsiInterface::with_id(0x00, NT35510_ID, 0x00);
+        let mut panel: MockPanel = PanelDriver::probe(dsi, MockLtdcInterface::new(), MockFramebuffer::new(LCD_HEIGHT, LCD_WIDTH)).unwrap();
+        assert_eq!(panel.panel(), Panel::Nt35510);
+
+        panel.init(OTM8009A_FORMAT_RGB565, OTM8009A_ORIENTATION_PORTRAIT).unwrap();
+        assert_eq!(panel.get_dimensions(), (LCD_HEIGHT, LCD_WIDTH));
+    }
+
+    #[test]
+    fn test_unknown_panel() {
+        let id = PanelId { manufacturer: 0x12, version: 0x34, driver: 0x56 };
+        let result = probe(MockDsiInterface::with_id(0x12, 0x34, 0x56));
+
+        assert_eq!(result.err(), Some(DisplayError::UnknownPanel(id)));
+    }
+
+    #[test]
+    fn test_probe_failure_keeps_dsi_error() {
+        let mut dsi = MockDsiInterface::new();
+        dsi.set_should_fail(true);
+
+        let error = DsiError {
+            context: ErrorContext { opcode: Some(0xDA), stage: Some(InitStage::ReadId) },
+            error: MockDsiError::SimulatedFailure,
+        };
+        assert_eq!(probe(dsi).err(), Some(DisplayError::Dsi(error)));
+    }
+
+    #[test]
+    fn test_errors_are_converted() {
+        let mut panel = probe(MockDsiInterface::new()).unwrap();
+
+        assert_eq!(panel.set_pixel(0, 0, Rgb565(0xFFFF)), Err(DisplayError::NotReady));
+        assert_eq!(panel.init(0x12, OTM8009A_ORIENTATION_LANDSCAPE), Err(DisplayError::InvalidConfig));
+    }
+}
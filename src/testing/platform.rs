@@ -0,0 +1,37 @@
+//! Tests for the platform framebuffer claim
+
+#[cfg(all(test, feature = "nifs"))]
+mod tests {
+    use crate::platform::*;
+    use crate::traits::*;
+
+    const LEN: usize = 64;
+
+    static mut MEMORY: [u16; LEN] = [0; LEN];
+
+    /// Stand-in for the hook the platform port provides
+    #[no_mangle]
+    extern "C" fn avmgl_framebuffer(len: *mut usize) -> *mut u16 {
+        unsafe {
+            *len = LEN;
+            core::ptr::addr_of_mut!(MEMORY) as *mut u16
+        }
+    }
+
+    #[test]
+    fn test_framebuffer_is_claimed_once() {
+        let framebuffer = take_framebuffer(8, 8).unwrap();
+        assert_eq!(framebuffer.get_dimensions(), (8, 8));
+        assert!(take_framebuffer(8, 8).is_none());
+        assert!(take_indexed_framebuffer(8, 8).is_none());
+
+        drop(framebuffer);
+        let indexed = take_indexed_framebuffer(16, 8).unwrap();
+        assert_eq!(indexed.get_dimensions(), (16, 8));
+        drop(indexed);
+
+        // A failed take does not keep the claim
+        assert!(take_framebuffer(16, 16).is_none());
+        assert!(take_framebuffer(8, 8).is_some());
+    }
+}
@@ -0,0 +1,106 @@
+//! Tests for the panel power state machine
+
+#[cfg(test)]
+mod tests {
+    use crate::otm8009a::defs::*;
+    use crate::testing::mocks::*;
+
+    fn opcodes(driver: &MockDriver) -> Vec<u8> {
+        driver.dsi().commands_sent.iter().map(|command| command.params[0]).collect()
+    }
+
+    #[test]
+    fn test_legal_steps() {
+        use PowerMode::*;
+
+        assert!(Off.can_step_to(Sleep) && Sleep.can_step_to(Off));
+        assert!(Sleep.can_step_to(DisplayOff) && DisplayOff.can_step_to(Normal));
+        assert!(PartialDisplay.can_step_to(IdleMode) && IdleMode.can_step_to(Normal));
+        assert!(!Off.can_step_to(Normal));
+        assert!(!Sleep.can_step_to(Normal));
+        assert!(!Normal.can_step_to(Sleep));
+        assert!(!DisplayOff.can_step_to(PartialDisplay));
+    }
+
+    #[test]
+    fn test_steps_down_and_up() {
+        let mut driver = init_driver(presets::STANDARD_LANDSCAPE);
+        driver.dsi_mut().clear_history();
+
+        assert_eq!(
+            driver.set_power_mode(PowerMode::Sleep),
+            Err(Otm8009aError::IllegalTransition { from: PowerMode::Normal, to: PowerMode::Sleep })
+        );
+
+        driver.set_power_mode(PowerMode::DisplayOff).unwrap();
+        assert!(!driver.ltdc().is_enabled());
+        driver.set_power_mode(PowerMode::Sleep).unwrap();
+        assert_eq!(
+            driver.set_power_mode(PowerMode::Normal),
+            Err(Otm8009aError::IllegalTransition { from: PowerMode::Sleep, to: PowerMode::Normal })
+        );
+        assert_eq!(driver.power_mode(), PowerMode::Sleep);
+
+        driver.set_power_mode(PowerMode::DisplayOff).unwrap();
+        driver.set_power_mode(PowerMode::Normal).unwrap();
+        assert!(driver.ltdc().is_enabled());
+
+        assert_eq!(opcodes(&driver), vec![
+            commands::DISPLAY_OFF,
+            commands::SLEEP_IN,
+            commands::SLEEP_OUT,
+            commands::DISPLAY_ON,
+        ]);
+        assert_eq!(driver.dsi().delays_requested, vec![
+            timing::DISPLAY_OFF_DELAY_MS,
+            timing::SLEEP_IN_DELAY_MS,
+            timing::SLEEP_OUT_DELAY_MS,
+            timing::DISPLAY_ON_DELAY_MS,
+        ]);
+    }
+
+    #[test]
+    fn test_power_off_and_on() {
+        let mut driver = init_driver(presets::STANDARD_LANDSCAPE);
+        driver.dsi_mut().clear_history();
+        let mut platform = MockPlatformInterface::new("mock");
+        assert_eq!(
+            driver.power_on(&mut platform),
+            Err(Otm8009aError::IllegalTransition { from: PowerMode::Normal, to: PowerMode::Sleep })
+        );
+
+        driver.enter_idle_mode().unwrap();
+        driver.dsi_mut().clear_history();
+        driver.power_off(&mut platform).unwrap();
+
+        assert_eq!(driver.power_mode(), PowerMode::Off);
+        assert!(platform.low_power_mode);
+        assert!(!driver.ltdc().is_enabled());
+        assert_eq!(opcodes(&driver), vec![commands::IDLE_MODE_OFF, commands::DISPLAY_OFF, commands::SLEEP_IN]);
+
+        // Nothing reaches the panel until the platform is back
+        driver.dsi_mut().clear_history();
+        assert_eq!(
+            driver.exit_sleep(),
+            Err(Otm8009aError::IllegalTransition { from: PowerMode::Off, to: PowerMode::Normal })
+        );
+        assert_eq!(driver.dsi().command_count(), 0);
+
+        driver.power_on(&mut platform).unwrap();
+        assert!(!platform.low_power_mode);
+        assert_eq!(driver.power_mode(), PowerMode::Sleep);
+        driver.exit_sleep().unwrap();
+        assert_eq!(driver.power_mode(), PowerMode::Normal);
+    }
+
+    #[test]
+    fn test_platform_failure_keeps_panel_asleep() {
+        let mut driver = init_driver(presets::STANDARD_LANDSCAPE);
+        driver.dsi_mut().clear_history();
+        let mut platform = MockPlatformInterface::new("mock");
+        platform.set_should_fail(true);
+
+        assert_eq!(driver.power_off(&mut platform), Err(Otm8009aError::CommError));
+        assert_eq!(driver.power_mode(), PowerMode::Sleep);
+    }
+}
@@ -0,0 +1,107 @@
+//! Tests for hardware reset
+
+#[cfg(test)]
+mod tests {
+    use crate::otm8009a::defs::*;
+    use crate::testing::mocks::*;
+
+    #[test]
+    fn test_power_mode_from_status() {
+        assert_eq!(PowerMode::from_status(0x08), PowerMode::Sleep);
+        assert_eq!(PowerMode::from_status(0x98), PowerMode::DisplayOff);
+        assert_eq!(PowerMode::from_status(0x9C), PowerMode::Normal);
+        assert_eq!(PowerMode::from_status(0xB4), PowerMode::PartialDisplay);
+        assert_eq!(PowerMode::from_status(0xDC), PowerMode::IdleMode);
+    }
+
+    #[test]
+    fn test_init_pulses_reset_pin() {
+        let mut driver = new_driver().with_reset_pin(MockOutputPin::new());
+        driver.init(OTM8009A_FORMAT_RGB565, OTM8009A_ORIENTATION_LANDSCAPE).unwrap();
+
+        assert_eq!(driver.reset_pin().unwrap().states, vec![false, true]);
+        assert_eq!(&driver.dsi().delays_requested[..2], &[timing::RESET_PULSE_MS, timing::RESET_RELEASE_MS]);
+        assert_eq!(driver.dsi().reads[0], commands::READ_ID1);
+    }
+
+    #[test]
+    fn test_reset_rederives_state() {
+        let mut driver = new_driver().with_reset_pin(MockOutputPin::new());
+        driver.init(OTM8009A_FORMAT_RGB565, OTM8009A_ORIENTATION_LANDSCAPE).unwrap();
+        assert_eq!(driver.power_mode(), PowerMode::Normal);
+
+        driver.reset().unwrap();
+
+        assert!(!driver.is_initialized());
+        assert_eq!(driver.power_mode(), PowerMode::Sleep);
+        assert_eq!(driver.reset_pin().unwrap().states, vec![false, true, false, true]);
+        assert_eq!(driver.dsi().delays_requested, vec![timing::RESET_PULSE_MS, timing::RESET_RELEASE_MS]);
+        assert_eq!(driver.dsi().reads, vec![commands::GET_POWER_MODE]);
+
+        // The panel comes back after a fresh init
+        driver.init(OTM8009A_FORMAT_RGB565, OTM8009A_ORIENTATION_LANDSCAPE).unwrap();
+        assert_eq!(driver.power_mode(), PowerMode::Normal);
+    }
+
+    #[test]
+    fn test_recover_pulses_reset_pin_once() {
+        let mut driver = new_driver().with_reset_pin(MockOutputPin::new());
+        driver.init(OTM8009A_FORMAT_RGB565, OTM8009A_ORIENTATION_LANDSCAPE).unwrap();
+        driver.dsi_mut().clear_history();
+
+        driver.recover().unwrap();
+
+        assert!(driver.is_initialized());
+        // One pulse from init, one from recover
+        assert_eq!(driver.reset_pin().unwrap().states, vec![false, true, false, true]);
+        assert_eq!(&driver.dsi().delays_requested[..2], &[timing::RESET_PULSE_MS, timing::RESET_RELEASE_MS]);
+    }
+
+    #[test]
+    fn test_reset_without_pin() {
+        let mut driver = new_driver();
+        driver.init(OTM8009A_FORMAT_RGB565, OTM8009A_ORIENTATION_LANDSCAPE).unwrap();
+
+        // A soft reset that leaves the panel awake shows up in RDDPM
+        driver.dsi_mut().set_register(commands::GET_POWER_MODE, &[0x9C]);
+        driver.reset().unwrap();
+
+        assert!(!driver.is_initialized());
+        assert_eq!(driver.power_mode(), PowerMode::Normal);
+        assert_eq!(driver.dsi().delays_requested, vec![timing::RESET_DELAY_MS]);
+        assert_eq!(driver.set_brightness(0x10), Err(Otm8009aError::NotReady));
+    }
+
+    #[cfg(feature = "embedded-hal")]
+    #[test]
+    fn test_embedded_hal_reset_pin() {
+        use core::convert::Infallible;
+        use embedded_hal::digital::{ErrorType, OutputPin};
+
+        #[derive(Default)]
+        struct HalPin {
+            states: Vec<bool>,
+        }
+
+        impl ErrorType for HalPin {
+            type Error = Infallible;
+        }
+
+        impl OutputPin for HalPin {
+            fn set_low(&mut self) -> Result<(), Infallible> {
+                self.states.push(false);
+                Ok(())
+            }
+
+            fn set_high(&mut self) -> Result<(), Infallible> {
+                self.states.push(true);
+                Ok(())
+            }
+        }
+
+        let mut driver = new_driver().with_reset_pin(HalPin::default());
+        driver.init(OTM8009A_FORMAT_RGB565, OTM8009A_ORIENTATION_LANDSCAPE).unwrap();
+
+        assert_eq!(driver.reset_pin().unwrap().states, vec![false, true]);
+    }
+}
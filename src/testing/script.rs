@@ -0,0 +1,87 @@
+//! Tests for the init script interpreter
+
+#[cfg(test)]
+mod tests {
+    use crate::otm8009a::defs::*;
+    use crate::script::{run_bus_script, run_script, InitStep::{self, *}, ORISE_ADDRESS_SHIFT};
+    use crate::testing::mocks::*;
+
+    const SCRIPT: &[InitStep] = &[
+        Write(&[0x11]),
+        Delay(120),
+        Shifted(0x80, &[0xC4, 0x30]),
+        Write(&[0x3A, 0x55]),
+    ];
+
+    #[test]
+    fn test_script_playback() {
+        let mut dsi = MockDsiInterface::new();
+
+        run_script(&mut dsi, SCRIPT).unwrap();
+
+        let sent: Vec<(usize, Vec<u8>)> = dsi.commands_sent.iter()
+            .map(|command| (command.nb_params, command.params.clone()))
+            .collect();
+        assert_eq!(sent, vec![
+            (0, vec![0x11]),
+            (1, vec![ORISE_ADDRESS_SHIFT, 0x80]),
+            (1, vec![0xC4, 0x30]),
+            (1, vec![0x3A, 0x55]),
+        ]);
+        assert_eq!(dsi.delays_requested, vec![120]);
+    }
+
+    #[test]
+    fn test_script_stops_on_failure() {
+        let mut dsi = MockDsiInterface::new();
+        dsi.set_should_fail(true);
+
+        assert!(run_script(&mut dsi, SCRIPT).is_err());
+        assert_eq!(dsi.command_count(), 0);
+        assert!(dsi.delays_requested.is_empty());
+    }
+
+    #[test]
+    fn test_otm8009a_scripts_are_well_formed() {
+        for step in scripts::POWER_UP.iter().chain(scripts::DISPLAY_ON) {
+            match step {
+                Write(payload) | Shifted(_, payload) => assert!(!payload.is_empty(), "{:?}", step),
+                Delay(ms) => assert!(*ms > 0),
+            }
+        }
+    }
+
+    #[test]
+    fn test_otm8009a_scripts_send_real_opcodes() {
+        let opcodes = |script: &[InitStep]| -> Vec<u8> {
+            script.iter()
+                .filter_map(|step| match step {
+                    Write(payload) | Shifted(_, payload) => Some(payload[0]),
+                    Delay(_) => None,
+                })
+                .collect()
+        };
+
+        assert_eq!(opcodes(scripts::POWER_UP).last(), Some(&commands::SLEEP_OUT));
+        assert!(opcodes(scripts::DISPLAY_ON).contains(&commands::DISPLAY_ON));
+        assert_eq!(opcodes(scripts::DISPLAY_ON).last(), Some(&commands::WRITE_MEMORY_START));
+    }
+
+    #[test]
+    fn test_bus_script_splits_command_and_data() {
+        let mut bus = MockDisplayBus::new();
+
+        run_bus_script(&mut bus, SCRIPT).unwrap();
+
+        assert_eq!(bus.writes, vec![
+            BusWrite::Command(0x11),
+            BusWrite::Command(ORISE_ADDRESS_SHIFT),
+            BusWrite::Data(vec![0x80]),
+            BusWrite::Command(0xC4),
+            BusWrite::Data(vec![0x30]),
+            BusWrite::Command(0x3A),
+            BusWrite::Data(vec![0x55]),
+        ]);
+        assert_eq!(bus.delays_requested, vec![120]);
+    }
+}
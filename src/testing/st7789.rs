@@ -0,0 +1,107 @@
+//! Tests for the ST7789 driver
+//!
+//! Drives `ST7789Driver` end to end against the mock DBI bus.
+//! Only built with the `st7789` feature.
+
+#[cfg(all(test, feature = "st7789"))]
+mod tests {
+    use crate::st7789::defs::*;
+    use crate::st7789::driver::St7789Spec;
+    use crate::error::{BusError, InitStage};
+    use crate::otm8009a::defs::{ColorFormat, DisplayConfig, Orientation, PowerMode};
+    use crate::testing::mocks::*;
+    use crate::traits::{pixel::Rgb565, DisplayPanel, PixelFormat};
+
+    fn failure(opcode: u8, stage: InitStage) -> St7789Error<MockBusError> {
+        St7789Error::Bus(BusError::command(opcode, MockBusError::SimulatedFailure).during(stage))
+    }
+
+    #[test]
+    fn test_init_sends_sequence() {
+        let mut driver = new_dbi_driver::<St7789Spec>();
+        driver.init(ST7789_FORMAT_RGB565, ST7789_ORIENTATION_LANDSCAPE).unwrap();
+
+        assert!(driver.is_initialized());
+        assert_eq!(driver.get_dimensions(), (LCD_WIDTH, LCD_HEIGHT));
+
+        let commands = driver.bus().commands();
+        assert_eq!(commands[0], commands::SOFT_RESET);
+        let position = |command: u8| commands.iter().position(|sent| *sent == command).unwrap();
+        assert!(position(commands::SLEEP_OUT) < position(commands::SET_PIXEL_FORMAT));
+        assert!(position(commands::SET_MEMORY_ACCESS_CONTROL) < position(commands::DISPLAY_ON));
+        assert!(driver.bus().writes.contains(&BusWrite::Data(vec![0x60])));
+        assert!(driver.bus().delays_requested.contains(&timing::SLEEP_OUT_DELAY_MS));
+    }
+
+    #[test]
+    fn test_portrait_swaps_dimensions() {
+        let mut driver = new_dbi_driver::<St7789Spec>();
+        driver.init(ST7789_FORMAT_RGB666, ST7789_ORIENTATION_PORTRAIT).unwrap();
+
+        assert_eq!(driver.get_dimensions(), (LCD_HEIGHT, LCD_WIDTH));
+        assert_eq!(driver.init(0x77, ST7789_ORIENTATION_PORTRAIT), Err(St7789Error::InvalidConfig));
+    }
+
+    #[test]
+    fn test_drawing_is_clipped_and_streamed() {
+        let mut driver = new_dbi_driver::<St7789Spec>();
+        assert_eq!(driver.clear(Rgb565(0x0000)), Err(St7789Error::NotReady));
+
+        driver.init(ST7789_FORMAT_RGB565, ST7789_ORIENTATION_LANDSCAPE).unwrap();
+        driver.bus_mut().clear_history();
+
+        driver.fill_rect(310, 230, 20, 20, Rgb565(0xF800)).unwrap();
+        assert_eq!(driver.bus().writes.last(), Some(&BusWrite::Repeated(vec![0xF8, 0x00], 100)));
+
+        driver.set_pixel(0, 0, Rgb565(0x001F)).unwrap();
+        assert_eq!(driver.bus().writes.last(), Some(&BusWrite::Data(vec![0x00, 0x1F])));
+
+        assert_eq!(driver.set_pixel(LCD_WIDTH, 0, Rgb565(0x001F)), Err(St7789Error::InvalidCoordinates));
+    }
+
+    #[test]
+    fn test_driver_as_display_panel() {
+        let mut driver = new_dbi_driver::<St7789Spec>();
+        let config = DisplayConfig::new(Orientation::Portrait, ColorFormat::Rgb565);
+        DisplayPanel::init(&mut driver, config).unwrap();
+        assert_eq!(DisplayPanel::get_pixel_format(&driver), PixelFormat::Rgb565);
+        assert_eq!(DisplayPanel::get_dimensions(&driver), (LCD_HEIGHT, LCD_WIDTH));
+        assert_eq!(DisplayPanel::power_mode(&driver), PowerMode::Normal);
+
+        driver.bus_mut().clear_history();
+        DisplayPanel::enter_sleep(&mut driver).unwrap();
+        DisplayPanel::exit_sleep(&mut driver).unwrap();
+        DisplayPanel::set_power_mode(&mut driver, PowerMode::IdleMode).unwrap();
+        assert_eq!(driver.bus().commands(), vec![0x28, 0x10, 0x11, 0x29, 0x39]);
+        assert_eq!(
+            DisplayPanel::set_power_mode(&mut driver, PowerMode::Off),
+            Err(St7789Error::IllegalTransition { from: PowerMode::IdleMode, to: PowerMode::Off })
+        );
+        DisplayPanel::flush(&mut driver).unwrap();
+    }
+
+    #[test]
+    fn test_display_on_enables_inversion() {
+        let mut driver = new_dbi_driver::<St7789Spec>();
+        driver.init(ST7789_FORMAT_RGB565, ST7789_ORIENTATION_PORTRAIT).unwrap();
+
+        let commands = driver.bus().commands();
+        assert_eq!(&commands[commands.len() - 3..], &[commands::INVERSION_ON, commands::NORMAL_MODE_ON, commands::DISPLAY_ON]);
+    }
+
+    #[test]
+    fn test_bus_failure_keeps_bus_error() {
+        let mut driver = new_dbi_driver::<St7789Spec>();
+        driver.bus_mut().set_should_fail(true);
+
+        assert_eq!(driver.init(ST7789_FORMAT_RGB565, ST7789_ORIENTATION_LANDSCAPE), Err(failure(commands::SOFT_RESET, InitStage::PowerUp(0))));
+        assert!(!driver.is_initialized());
+
+        // Drawing failures name the command but have no stage
+        driver.bus_mut().set_should_fail(false);
+        driver.init(ST7789_FORMAT_RGB565, ST7789_ORIENTATION_LANDSCAPE).unwrap();
+        driver.bus_mut().set_should_fail(true);
+        let error = BusError::command(commands::SET_COLUMN_ADDRESS, MockBusError::SimulatedFailure);
+        assert_eq!(driver.set_pixel(0, 0, Rgb565(0x001F)), Err(St7789Error::Bus(error)));
+    }
+}
@@ -0,0 +1,95 @@
+//! Tests for double and triple buffering
+
+#[cfg(test)]
+mod tests {
+    use crate::otm8009a::defs::*;
+    use crate::otm8009a::driver::OTM8009ADriver;
+    use crate::swapchain::SwapChain;
+    use crate::testing::mocks::*;
+    use crate::traits::{pixel::Rgb565, *};
+
+    const RED: Rgb565 = Rgb565(0xF800);
+    const BLUE: Rgb565 = Rgb565(0x001F);
+
+    fn new_chain<const N: usize>() -> SwapChain<MockFramebuffer, N> {
+        SwapChain::new(core::array::from_fn(|_| MockFramebuffer::new(8, 8))).unwrap()
+    }
+
+    #[test]
+    fn test_rejects_mismatched_buffers() {
+        assert!(SwapChain::new([MockFramebuffer::<Rgb565>::new(8, 8)]).is_none());
+        assert!(SwapChain::new([MockFramebuffer::<Rgb565>::new(8, 8), MockFramebuffer::new(8, 4)]).is_none());
+        assert!(SwapChain::new([MockFramebuffer::<Rgb565>::new(8, 8), MockFramebuffer::new(8, 8)]).is_some());
+    }
+
+    #[test]
+    fn test_drawing_goes_to_back_buffer() {
+        let mut chain = new_chain::<2>();
+        let front = chain.get_buffer_ptr();
+
+        chain.fill_rect(0, 0, 2, 2, RED);
+        assert_eq!(chain.back().get_pixel(1, 1), Some(RED));
+        assert_eq!(chain.front().get_pixel(1, 1), Some(Rgb565(0)));
+        assert_eq!(chain.get_row(1).unwrap()[1], Rgb565(0));
+
+        chain.flip();
+        assert_ne!(chain.get_buffer_ptr(), front);
+        assert_eq!(chain.front().get_pixel(1, 1), Some(RED));
+    }
+
+    #[test]
+    fn test_restore_copies_damage_only_when_enabled() {
+        let mut chain = new_chain::<2>();
+        chain.set_pixel(3, 4, RED);
+        chain.flip();
+        chain.restore_back();
+        assert_eq!(chain.back().get_pixel(3, 4), Some(Rgb565(0)));
+
+        let mut chain = new_chain::<2>();
+        chain.set_restore_back(true);
+        chain.set_pixel(3, 4, RED);
+        chain.flip();
+        chain.restore_back();
+        assert_eq!(chain.back().get_pixel(3, 4), Some(RED));
+        assert_eq!(chain.back().get_pixel(4, 4), Some(Rgb565(0)));
+    }
+
+    #[test]
+    fn test_triple_buffering_restores_two_frames() {
+        let mut chain = new_chain::<3>();
+        chain.set_restore_back(true);
+
+        chain.set_pixel(0, 0, RED);
+        chain.flip();
+        chain.restore_back();
+        chain.set_pixel(7, 7, BLUE);
+        chain.flip();
+        chain.restore_back();
+
+        // The buffer shown first missed both frames
+        assert_eq!(chain.back().get_pixel(0, 0), Some(RED));
+        assert_eq!(chain.back().get_pixel(7, 7), Some(BLUE));
+    }
+
+    #[test]
+    fn test_driver_flip() {
+        let buffers = core::array::from_fn(|_| MockFramebuffer::<Rgb565>::new(LCD_WIDTH, LCD_HEIGHT));
+        let mut driver = OTM8009ADriver::new(MockDsiInterface::new(), MockLtdcInterface::new(), SwapChain::<_, 2>::new(buffers).unwrap());
+        assert_eq!(driver.flip(), Err(Otm8009aError::NotReady));
+
+        driver.init(OTM8009A_FORMAT_RGB565, OTM8009A_ORIENTATION_LANDSCAPE).unwrap();
+        let shown = driver.ltdc().framebuffer_addresses[0].1;
+        driver.fill_rect(0, 0, 10, 10, RED).unwrap();
+        driver.flip().unwrap();
+
+        assert_ne!(driver.ltdc().framebuffer_addresses[0].1, shown);
+        assert_eq!(driver.ltdc().framebuffer_addresses[0].1, driver.framebuffer().get_buffer_ptr() as u32);
+        assert_eq!(driver.ltdc().vblank_waits, 1);
+
+        let buffers = core::array::from_fn(|_| MockFramebuffer::<Rgb565>::new(LCD_WIDTH, LCD_HEIGHT));
+        let mut driver = OTM8009ADriver::new(MockDsiInterface::new(), MockLtdcInterface::new(), SwapChain::<_, 3>::new(buffers).unwrap());
+        driver.init(OTM8009A_FORMAT_RGB565, OTM8009A_ORIENTATION_LANDSCAPE).unwrap();
+        driver.flip().unwrap();
+        assert_eq!(driver.ltdc().vblank_waits, 0);
+    }
+}
@@ -32,10 +32,10 @@ pub trait LtdcTestingExt: LtdcInterface {
 /// Testing extensions for framebuffer interface
 pub trait FramebufferTestingExt: FramebufferInterface {
     /// Get a pixel value for verification
-    fn get_pixel(&self, x: u16, y: u16) -> Option<u16>;
+    fn get_pixel(&self, x: u16, y: u16) -> Option<Self::Pixel>;
     
     /// Verify framebuffer contents
-    fn verify_region(&self, x: u16, y: u16, width: u16, height: u16, expected_color: u16) -> bool;
+    fn verify_region(&self, x: u16, y: u16, width: u16, height: u16, expected_color: Self::Pixel) -> bool;
 }
 
 #[derive(Debug, Clone)]
@@ -44,12 +44,12 @@ pub struct DcsCommand {
     pub params: Vec<u8>,
 }
 
-impl FramebufferTestingExt for crate::testing::mocks::MockFramebuffer {
-    fn get_pixel(&self, x: u16, y: u16) -> Option<u16> {
+impl<P: PixelType> FramebufferTestingExt for crate::testing::mocks::MockFramebuffer<P> {
+    fn get_pixel(&self, x: u16, y: u16) -> Option<P> {
         self.get_pixel(x, y)
     }
     
-    fn verify_region(&self, x: u16, y: u16, width: u16, height: u16, expected_color: u16) -> bool {
+    fn verify_region(&self, x: u16, y: u16, width: u16, height: u16, expected_color: P) -> bool {
         for row in y..(y + height) {
             for col in x..(x + width) {
                 if let Some(pixel) = self.get_pixel(col, row) {
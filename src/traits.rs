@@ -2,15 +2,15 @@
 //! 
 //! These traits define the hardware abstraction layer for the display driver.
 
-extern crate alloc;
-
-use alloc::vec::Vec;
+use crate::otm8009a::defs::{DisplayConfig, Orientation, PowerMode};
 
 /// DSI (Display Serial Interface) trait for sending commands to the display
 pub trait DsiInterface {
     type Error;
     
     /// Send a DCS (Display Command Set) command to the display
+    ///
+    /// `params` holds the command byte followed by `nb_params` parameters.
     fn send_dcs_command(&mut self, nb_params: usize, params: &[u8]) -> Result<(), Self::Error>;
     
     /// Delay for the specified number of milliseconds
@@ -21,6 +21,51 @@ pub trait DsiInterface {
     
     /// Reset the DSI interface
     fn reset(&mut self) -> Result<(), Self::Error>;
+
+    /// Set the maximum number of bytes the display may return for one read
+    fn set_max_return_packet_size(&mut self, size: u16) -> Result<(), Self::Error>;
+
+    /// Send a DCS read request for `command` and store the response in `buffer`
+    fn dcs_read(&mut self, command: u8, buffer: &mut [u8]) -> Result<(), Self::Error>;
+
+    /// Block until the panel signals a tearing effect event
+    fn wait_for_tearing_effect(&mut self) -> Result<(), Self::Error>;
+
+    /// Read `buffer.len()` bytes of a DCS register
+    ///
+    /// Sets the maximum return packet size first so the display sends the
+    /// whole response.
+    fn read_register(&mut self, command: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.set_max_return_packet_size(buffer.len() as u16)?;
+        self.dcs_read(command, buffer)
+    }
+}
+
+/// MIPI DBI bus to a panel with its own frame memory
+///
+/// Covers Type C (SPI with a D/C line) and Type B (8080 parallel) interfaces:
+/// command bytes are sent with D/C low, parameters and pixels with D/C high.
+pub trait DisplayBus {
+    type Error;
+
+    /// Send a command byte
+    fn write_command(&mut self, command: u8) -> Result<(), Self::Error>;
+
+    /// Send parameter or pixel bytes following a command
+    fn write_data(&mut self, data: &[u8]) -> Result<(), Self::Error>;
+
+    /// Stream `count` copies of one encoded pixel following a memory write
+    ///
+    /// Buses with DMA should override this to avoid one transfer per pixel.
+    fn write_repeated(&mut self, pixel: &[u8], count: u32) -> Result<(), Self::Error> {
+        for _ in 0..count {
+            self.write_data(pixel)?;
+        }
+        Ok(())
+    }
+
+    /// Delay for the specified number of milliseconds
+    fn delay_ms(&mut self, ms: u32);
 }
 
 /// LTDC (LCD-TFT Display Controller) trait for managing display layers
@@ -36,32 +81,199 @@ pub trait LtdcInterface {
     /// Disable the LTDC controller
     fn disable(&mut self) -> Result<(), Self::Error>;
     
+    /// Show a layer configured earlier
+    fn enable_layer(&mut self, layer: u8) -> Result<(), Self::Error>;
+
+    /// Hide a layer without losing its configuration
+    fn disable_layer(&mut self, layer: u8) -> Result<(), Self::Error>;
+    
+    /// Load a layer's color lookup table from `0x00RRGGBB` entries, starting
+    /// at index 0, and enable it
+    fn load_clut(&mut self, layer: u8, colors: &[u32]) -> Result<(), Self::Error>;
+    
     /// Set the framebuffer address for a specific layer
     fn set_framebuffer(&mut self, layer: u8, address: u32) -> Result<(), Self::Error>;
     
     /// Get display dimensions
     fn get_dimensions(&self) -> (u16, u16);
+
+    /// Block until the next vertical blanking period starts
+    fn wait_for_vblank(&mut self) -> Result<(), Self::Error>;
 }
 
 /// Framebuffer trait for pixel manipulation
 pub trait FramebufferInterface {
+    /// Pixel type stored in the framebuffer
+    type Pixel: PixelType;
+
     /// Fill a rectangular region with a color
-    fn fill_rect(&mut self, x: u16, y: u16, width: u16, height: u16, color: u16);
+    fn fill_rect(&mut self, x: u16, y: u16, width: u16, height: u16, color: Self::Pixel);
     
     /// Set a single pixel
-    fn set_pixel(&mut self, x: u16, y: u16, color: u16);
+    fn set_pixel(&mut self, x: u16, y: u16, color: Self::Pixel);
     
     /// Clear the entire framebuffer with a color
-    fn clear(&mut self, color: u16);
+    fn clear(&mut self, color: Self::Pixel);
     
     /// Get framebuffer dimensions
     fn get_dimensions(&self) -> (u16, u16);
+
+    /// Get one visible line, without its padding
+    fn get_row(&self, y: u16) -> Option<&[Self::Pixel]>;
+    
+    /// Copy `pixels` into line `y` starting at column `x`, clipped to the
+    /// framebuffer
+    fn write_row(&mut self, x: u16, y: u16, pixels: &[Self::Pixel]) {
+        for (col, &pixel) in (x..=u16::MAX).zip(pixels) {
+            self.set_pixel(col, y, pixel);
+        }
+    }
     
     /// Get pointer to framebuffer data
-    fn get_buffer_ptr(&self) -> *const u16;
+    fn get_buffer_ptr(&self) -> *const Self::Pixel;
     
     /// Get framebuffer size in bytes
     fn get_buffer_size(&self) -> usize;
+
+    /// Get the length of one line in bytes
+    fn get_pitch(&self) -> usize {
+        self.get_dimensions().0 as usize * Self::Pixel::BYTES_PER_PIXEL
+    }
+}
+
+/// Framebuffer made of several buffers that take turns being scanned out
+///
+/// Drawing goes to the back buffer, while `get_buffer_ptr` and `get_row`
+/// refer to the front buffer the LTDC shows.
+pub trait PageFlip: FramebufferInterface {
+    /// Number of buffers in rotation
+    fn buffer_count(&self) -> usize;
+
+    /// Make the back buffer the front buffer and move drawing on to the next
+    fn flip(&mut self);
+
+    /// Bring the back buffer up to date with the front buffer where it is
+    /// stale, if the framebuffer was asked to
+    fn restore_back(&mut self);
+}
+
+/// Pixel stored in a framebuffer, tied to the LTDC format it is scanned out as
+pub trait PixelType: Copy + Default + PartialEq + core::fmt::Debug + 'static {
+    /// LTDC pixel format matching the in-memory layout
+    const FORMAT: PixelFormat;
+
+    /// Size of one pixel in bytes
+    const BYTES_PER_PIXEL: usize = Self::FORMAT.bytes_per_pixel();
+}
+
+/// Pixel that can be written to panel GRAM with a DCS memory write
+pub trait DcsPixel: PixelType {
+    /// Size of one pixel on the link in bytes
+    const DCS_BYTES: usize;
+
+    /// Encode the pixel into `out` in the order the panel expects
+    fn write_dcs_bytes(self, out: &mut [u8]);
+}
+
+/// Display panel driver, independent of the controller behind it
+///
+/// Covers bring-up, drawing and the power state machine, so the NIFs and
+/// embedded-graphics can drive any panel.
+pub trait DisplayPanel {
+    type Error;
+
+    /// Pixel drawn to the panel
+    type Pixel: PixelType;
+
+    /// Bring up the panel and leave it in the power mode of `config`
+    fn init(&mut self, config: DisplayConfig) -> Result<(), Self::Error>;
+
+    /// Check if `init` has completed
+    fn is_initialized(&self) -> bool;
+
+    /// Change the scan direction
+    fn set_orientation(&mut self, orientation: Orientation) -> Result<(), Self::Error>;
+
+    /// Get the format of the pixels drawn to the panel
+    fn get_pixel_format(&self) -> PixelFormat {
+        Self::Pixel::FORMAT
+    }
+
+    /// Get display dimensions for the current orientation
+    fn get_dimensions(&self) -> (u16, u16);
+
+    /// Fill a rectangle, clipped to the display
+    fn fill_rect(&mut self, x: u16, y: u16, width: u16, height: u16, color: Self::Pixel) -> Result<(), Self::Error>;
+
+    /// Set a single pixel
+    fn set_pixel(&mut self, x: u16, y: u16, color: Self::Pixel) -> Result<(), Self::Error>;
+
+    /// Fill the whole display
+    fn clear(&mut self, color: Self::Pixel) -> Result<(), Self::Error>;
+
+    /// Make everything drawn so far visible on the panel
+    fn flush(&mut self) -> Result<(), Self::Error>;
+
+    /// Get the power mode the panel was last put in
+    fn power_mode(&self) -> PowerMode;
+
+    /// Take one step through the power state machine
+    fn set_power_mode(&mut self, mode: PowerMode) -> Result<(), Self::Error>;
+
+    /// Turn the display off and put the panel to sleep
+    fn enter_sleep(&mut self) -> Result<(), Self::Error>;
+
+    /// Wake the panel up and turn the display on
+    fn exit_sleep(&mut self) -> Result<(), Self::Error>;
+
+    /// Set the display brightness
+    fn set_brightness(&mut self, brightness: u8) -> Result<(), Self::Error>;
+}
+
+/// Digital output pin, compatible with embedded-hal's `OutputPin`
+pub trait OutputPin {
+    type Error;
+
+    /// Drive the pin low
+    fn set_low(&mut self) -> Result<(), Self::Error>;
+
+    /// Drive the pin high
+    fn set_high(&mut self) -> Result<(), Self::Error>;
+}
+
+/// Any embedded-hal output pin can drive XRES or a GPIO backlight
+#[cfg(feature = "embedded-hal")]
+impl<T: embedded_hal::digital::OutputPin> OutputPin for T {
+    type Error = T::Error;
+
+    fn set_low(&mut self) -> Result<(), T::Error> {
+        embedded_hal::digital::OutputPin::set_low(self)
+    }
+
+    fn set_high(&mut self) -> Result<(), T::Error> {
+        embedded_hal::digital::OutputPin::set_high(self)
+    }
+}
+
+/// Backlight driven by the board rather than through the panel's DCS
+/// brightness, e.g. a PWM or GPIO pin
+pub trait Backlight {
+    type Error;
+
+    /// Switch the backlight on or off, keeping its level
+    fn set_enabled(&mut self, enabled: bool) -> Result<(), Self::Error>;
+
+    /// Set the light output, from 0 (dark) to 255 (full)
+    fn set_level(&mut self, level: u8) -> Result<(), Self::Error>;
+
+    /// Map a perceived brightness to a light output level
+    ///
+    /// The eye is more sensitive to changes in dim light, so by default the
+    /// output follows the square of `brightness`.
+    fn perceptual_level(&self, brightness: u8) -> u8 {
+        let brightness = brightness as u16;
+        (brightness * brightness).div_ceil(255) as u8
+    }
 }
 
 /// Platform-specific interface trait
@@ -81,8 +293,15 @@ pub trait PlatformInterface {
     fn exit_low_power(&mut self) -> Result<(), Self::Error>;
 }
 
+/// Number of LTDC layers
+pub const LTDC_LAYER_COUNT: u8 = 2;
+
 /// Layer configuration for LTDC
+///
+/// The window spans `window_x0..window_x1` and `window_y0..window_y1` in
+/// screen coordinates; outside it the layer shows `default_color`.
 #[derive(Debug, Clone)]
+#[repr(C)]
 pub struct LayerConfig {
     pub layer: u8,
     pub window_x0: u16,
@@ -90,23 +309,283 @@ pub struct LayerConfig {
     pub window_y0: u16,
     pub window_y1: u16,
     pub pixel_format: PixelFormat,
+    /// Constant alpha applied to the whole layer
     pub alpha: u8,
     pub red_blue_swap: bool,
     pub framebuffer_address: u32,
     pub framebuffer_pitch: u16,
+    /// How the layer is blended with the layers below
+    pub blending: Blending,
+    /// ARGB8888 color outside the window
+    pub default_color: u32,
+    /// Make RGB888 `color_key` pixels transparent
+    pub color_key_enabled: bool,
+    pub color_key: u32,
+    pub enabled: bool,
+}
+
+impl LayerConfig {
+    /// Opaque layer scanning out `framebuffer` in a `width` x `height` window
+    /// at the origin
+    pub fn new<F: FramebufferInterface>(layer: u8, framebuffer: &F, width: u16, height: u16) -> Self {
+        Self {
+            layer,
+            window_x0: 0,
+            window_x1: width,
+            window_y0: 0,
+            window_y1: height,
+            pixel_format: F::Pixel::FORMAT,
+            alpha: 255,
+            red_blue_swap: false,
+            framebuffer_address: framebuffer.get_buffer_ptr() as u32,
+            framebuffer_pitch: framebuffer.get_pitch() as u16,
+            blending: Blending::PixelAlphaTimesConstantAlpha,
+            default_color: 0,
+            color_key_enabled: false,
+            color_key: 0,
+            enabled: true,
+        }
+    }
+
+    /// Check that the layer exists and its window is non-empty and inside a
+    /// `width` x `height` screen
+    pub fn is_valid(&self, width: u16, height: u16) -> bool {
+        self.layer < LTDC_LAYER_COUNT
+            && self.window_x0 < self.window_x1
+            && self.window_x1 <= width
+            && self.window_y0 < self.window_y1
+            && self.window_y1 <= height
+    }
+}
+
+/// LTDC blending factors, declared for C as `AVMGL_BLENDING_*`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum Blending {
+    /// Blend with the constant alpha only
+    ConstantAlpha = 0,
+    /// Blend with the pixel alpha multiplied by the constant alpha
+    PixelAlphaTimesConstantAlpha = 1,
+}
+
+/// Panel identification read from RDID1..RDID3
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PanelId {
+    /// Module manufacturer ID (0xDA)
+    pub manufacturer: u8,
+    /// Module/driver version (0xDB)
+    pub version: u8,
+    /// Module/driver ID (0xDC)
+    pub driver: u8,
+}
+
+impl PanelId {
+    /// Value read from the RDID register `command`, or `None` if it is not
+    /// one of them
+    pub const fn register(&self, command: u8) -> Option<u8> {
+        match command {
+            0xDA => Some(self.manufacturer),
+            0xDB => Some(self.version),
+            0xDC => Some(self.driver),
+            _ => None,
+        }
+    }
+}
+
+/// How the panel is refreshed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RefreshMode {
+    /// The LTDC streams every frame to the panel
+    #[default]
+    Video,
+    /// The panel refreshes from its own GRAM, which is written on demand
+    Command,
+}
+
+/// Tearing effect output selected with TEON (0x35)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum TearMode {
+    /// Pulse during vertical blanking only
+    VBlank = 0x00,
+    /// Pulse during both vertical and horizontal blanking
+    VHBlank = 0x01,
+}
+
+/// Content adaptive backlight control modes written with WRCABC (0x55)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum CabcMode {
+    #[default]
+    Off = 0x00,
+    /// User interface images
+    Ui = 0x01,
+    /// Still pictures
+    Still = 0x02,
+    /// Moving images
+    Moving = 0x03,
+}
+
+/// Backlight control bits written with WRCTRLD (0x53)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CtrlDisplay {
+    /// BCTRL: drive the backlight from the brightness register
+    pub brightness_control: bool,
+    /// DD: dim smoothly between brightness levels
+    pub dimming: bool,
+    /// BL: backlight on
+    pub backlight: bool,
+}
+
+impl CtrlDisplay {
+    /// Get the WRCTRLD parameter byte
+    pub const fn bits(self) -> u8 {
+        ((self.brightness_control as u8) << 5) | ((self.dimming as u8) << 3) | ((self.backlight as u8) << 2)
+    }
+}
+
+impl Default for CtrlDisplay {
+    /// Everything on, as written during init
+    fn default() -> Self {
+        Self { brightness_control: true, dimming: true, backlight: true }
+    }
 }
 
 /// Supported pixel formats
+///
+/// Discriminants are the LTDC `PF` register values, declared for C as
+/// `AVMGL_PF_*` in `c/avmgl_platform.h`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
 pub enum PixelFormat {
-    Argb8888,
-    Rgb888,
-    Rgb565,
-    Argb1555,
-    Argb4444,
-    L8,
-    Al44,
-    Al88,
+    Argb8888 = 0,
+    Rgb888 = 1,
+    Rgb565 = 2,
+    Argb1555 = 3,
+    Argb4444 = 4,
+    L8 = 5,
+    Al44 = 6,
+    Al88 = 7,
+}
+
+impl PixelFormat {
+    /// Size of one pixel in bytes
+    pub const fn bytes_per_pixel(self) -> usize {
+        match self {
+            PixelFormat::Argb8888 => 4,
+            PixelFormat::Rgb888 => 3,
+            PixelFormat::Rgb565 | PixelFormat::Argb1555 | PixelFormat::Argb4444 => 2,
+            PixelFormat::L8 | PixelFormat::Al44 => 1,
+            PixelFormat::Al88 => 2,
+        }
+    }
+
+    /// Number of CLUT entries an indexed format can address, 0 for direct color
+    pub const fn clut_size(self) -> usize {
+        match self {
+            PixelFormat::L8 | PixelFormat::Al88 => 256,
+            PixelFormat::Al44 => 16,
+            _ => 0,
+        }
+    }
+}
+
+/// Typed pixels for each LTDC pixel format
+pub mod pixel {
+    use super::{DcsPixel, PixelFormat, PixelType};
+
+    macro_rules! pixel_type {
+        ($(#[$doc:meta])* $name:ident($repr:ty)) => {
+            $(#[$doc])*
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+            #[repr(transparent)]
+            pub struct $name(pub $repr);
+
+            impl PixelType for $name {
+                const FORMAT: PixelFormat = PixelFormat::$name;
+            }
+        };
+    }
+
+    pixel_type!(
+        /// 32-bit `0xAARRGGBB`
+        Argb8888(u32)
+    );
+    pixel_type!(
+        /// 24-bit color stored as `[B, G, R]`
+        Rgb888([u8; 3])
+    );
+    pixel_type!(
+        /// 16-bit `RRRRRGGGGGGBBBBB`
+        Rgb565(u16)
+    );
+    pixel_type!(
+        /// 16-bit `ARRRRRGGGGGBBBBB`
+        Argb1555(u16)
+    );
+    pixel_type!(
+        /// 16-bit `AAAARRRRGGGGBBBB`
+        Argb4444(u16)
+    );
+    pixel_type!(
+        /// 8-bit CLUT index
+        L8(u8)
+    );
+    pixel_type!(
+        /// 4-bit alpha, 4-bit CLUT index
+        Al44(u8)
+    );
+    pixel_type!(
+        /// 8-bit alpha, 8-bit CLUT index
+        Al88(u16)
+    );
+
+    /// 16 bits per pixel, most significant byte first
+    impl DcsPixel for Rgb565 {
+        const DCS_BYTES: usize = 2;
+
+        fn write_dcs_bytes(self, out: &mut [u8]) {
+            out[..2].copy_from_slice(&self.0.to_be_bytes());
+        }
+    }
+
+    /// 24 bits per pixel as R, G, B
+    impl DcsPixel for Rgb888 {
+        const DCS_BYTES: usize = 3;
+
+        fn write_dcs_bytes(self, out: &mut [u8]) {
+            let [b, g, r] = self.0;
+            out[..3].copy_from_slice(&[r, g, b]);
+        }
+    }
+
+    /// 24 bits per pixel as R, G, B; the panel has no use for alpha
+    impl DcsPixel for Argb8888 {
+        const DCS_BYTES: usize = 3;
+
+        fn write_dcs_bytes(self, out: &mut [u8]) {
+            let [_, r, g, b] = self.0.to_be_bytes();
+            out[..3].copy_from_slice(&[r, g, b]);
+        }
+    }
+
+    impl Argb8888 {
+        pub const fn from_argb(a: u8, r: u8, g: u8, b: u8) -> Self {
+            Self(((a as u32) << 24) | ((r as u32) << 16) | ((g as u32) << 8) | b as u32)
+        }
+    }
+
+    impl Rgb888 {
+        pub const fn from_rgb(r: u8, g: u8, b: u8) -> Self {
+            Self([b, g, r])
+        }
+    }
+
+    impl Rgb565 {
+        pub const fn from_rgb(r: u8, g: u8, b: u8) -> Self {
+            Self((((r >> 3) as u16) << 11) | (((g >> 2) as u16) << 5) | (b >> 3) as u16)
+        }
+    }
 }
 
 /// Color conversion utilities